@@ -1,23 +1,22 @@
 //! TODO: make this a separate crate, in a separate project
 
-use std::default::default;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 
 pub(crate) trait CustomFormat {
 	type Options;
 
-	fn custom_format(&self, options: Self::Options) -> CustomFormatWrapper<Self> {
+	fn custom_format(&self, options: Self::Options) -> CustomFormatWrapper<'_, Self> {
 		CustomFormatWrapper {
 			value: self,
 			options,
 		}
 	}
 
-	fn default_format(&self) -> CustomFormatWrapper<Self> where Self::Options: Default {
+	fn default_format(&self) -> CustomFormatWrapper<'_, Self> where Self::Options: Default {
 		CustomFormatWrapper {
 			value: self,
-			options: default(),
+			options: Default::default(),
 		}
 	}
 
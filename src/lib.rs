@@ -178,17 +178,22 @@
 //! SpacedList must outlive Traversal
 //!
 
-#![feature(trait_alias)]
-#![feature(int_log)]
-#![feature(default_free_fn)]
-#![feature(option_get_or_insert_default)]
 #![allow(unused, clippy::collapsible_else_if)]
 
 mod spaced_lists;
 
 pub use spaced_lists::Spacing;
 pub use spaced_lists::SpacedList;
-pub use spaced_lists::hollow::HollowSpacedList;
+pub use spaced_lists::error::{InvariantViolation, SpacedListError, ViolationKind};
+pub use spaced_lists::memory::MemoryUsage;
+pub use spaced_lists::iter::{Iter, Range};
+pub use spaced_lists::cursor::CursorMut;
+pub use spaced_lists::hollow::{DuplicatePolicy, HollowSpacedList};
+pub use spaced_lists::filled::FilledSpacedList;
+pub use spaced_lists::aggregated::{Aggregate, AggregatedSpacedList};
+pub use spaced_lists::range::RangeSpacedList;
+pub use spaced_lists::filled_range::{FilledRangeSpacedList, Ranges};
+pub use spaced_lists::nested_range::{NestedRangeSpacedList, StickySide};
 
 mod custom_fmt;
 
@@ -0,0 +1,328 @@
+use std::collections::TryReserveError;
+use std::ops::{Bound, RangeBounds};
+
+use crate::spaced_lists::{CrateSpacedList, SpacedList, Spacing};
+use crate::spaced_lists::memory::MemoryUsage;
+use crate::spaced_lists::skeleton::SpacedListSkeleton;
+use crate::spaced_lists::skeleton::aggregates::LinkAggregates;
+
+/// A summary of the values of a sequence of nodes, such as their sum, their maximum or their
+/// number, that an [`AggregatedSpacedList`] keeps up to date.
+///
+/// Aggregates form a monoid: [`Self::combine`] must be associative and [`Self::empty`] must be its
+/// identity. `combine` need not be commutative.
+pub trait Aggregate<T>: Clone {
+	/// The aggregate of no nodes.
+	fn empty() -> Self;
+
+	/// The aggregate of a single node with `value`.
+	fn of(value: &T) -> Self;
+
+	/// The aggregate of the nodes aggregated by `self`, followed by the ones aggregated by `other`.
+	fn combine(&self, other: &Self) -> Self;
+}
+
+/// A spaced list that stores a value for every node and keeps the [`Aggregate`] of the values of
+/// the nodes each link leads past, so that the values of all nodes within a range of positions
+/// can be aggregated without visiting them.
+pub struct AggregatedSpacedList<S: Spacing, T, A: Aggregate<T>> {
+	skeleton: SpacedListSkeleton<S, Self>,
+	slot_count: usize,
+	/// The values of the nodes in every slot after node zero, `None` for vacant slots.
+	values: Vec<Option<T>>,
+	aggregates: LinkAggregates<T, A>,
+}
+
+impl<S: Spacing, T, A: Aggregate<T>> Default for AggregatedSpacedList<S, T, A> {
+	fn default() -> Self {
+		Self {
+			skeleton: Default::default(),
+			slot_count: 0,
+			values: vec![],
+			aggregates: Default::default(),
+		}
+	}
+}
+
+impl<S: Spacing, T, A: Aggregate<T>> AggregatedSpacedList<S, T, A> {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Appends a node at `distance` after the last node.
+	pub fn append(&mut self, distance: S, value: T) {
+		self.append_node_with_value(distance, value)
+	}
+
+	/// Inserts a node at `position`, after all nodes that are already at that position.
+	pub fn insert(&mut self, position: S, value: T) {
+		self.insert_node_with_value(position, value)
+	}
+
+	/// The value of the node at `position`.
+	///
+	/// If there are multiple nodes at `position`, this returns the value of the last one.
+	pub fn get(&self, position: S) -> Option<&T> {
+		self.node_at(position).map(|traversal| traversal.list.value_at(traversal.node_index))
+	}
+
+	/// Replaces the value of the node at `position` and returns the previous one.
+	///
+	/// If there are multiple nodes at `position`, the value of the last one is replaced.
+	pub fn set(&mut self, position: S, value: T) -> Option<T> {
+		let traversal = self.node_at(position)?;
+		let path = traversal.sublist_path();
+		let node_index = traversal.node_index;
+		Some(self.replace_value_at_path(&path, node_index, value))
+	}
+
+	/// Removes the node at `position` and returns its value, keeping all other nodes at their
+	/// positions.
+	///
+	/// If there are multiple nodes at `position`, the last one is removed.
+	pub fn remove(&mut self, position: S) -> Option<T> {
+		self.take_node_at(position)
+	}
+
+	/// The aggregate of the values of all nodes.
+	pub fn aggregate(&self) -> A {
+		self.aggregates.total()
+	}
+
+	/// The aggregate of the values of all nodes within `bounds`, in order, which is empty if
+	/// `bounds` contains no positions.
+	///
+	/// This takes time logarithmic in the size of every list it descends into, and descends into at
+	/// most two sublists per level.
+	pub fn aggregate_range<R: RangeBounds<S>>(&self, bounds: R) -> A {
+		let count_up_to = |position| self.node_at_or_before(position)
+			.map_or(0, |traversal| traversal.node_count_up_to());
+		let start = match bounds.start_bound() {
+			Bound::Included(&start) => self.rank(start),
+			Bound::Excluded(&start) => count_up_to(start),
+			Bound::Unbounded => 0
+		};
+		let end = match bounds.end_bound() {
+			Bound::Included(&end) => count_up_to(end),
+			Bound::Excluded(&end) => self.rank(end),
+			Bound::Unbounded => self.deep_size()
+		};
+		self.aggregate_by_index(start, end)
+	}
+
+	/// The aggregate of the values of the nodes with at least `start` and less than `end` nodes
+	/// before them, counting the nodes of sublists.
+	fn aggregate_by_index(&self, start: usize, end: usize) -> A {
+		if start >= end {
+			return A::empty();
+		}
+		// the nodes of this list's skeleton are included from the one after first up to last
+		let (first, first_count) = self.skeleton.last_node_with_count_up_to_at_most(self.slot_count, start);
+		let (last, last_count) = self.skeleton.last_node_with_count_up_to_at_most(self.slot_count, end);
+		let sublist_aggregate = |node_index: usize, start: usize, end: Option<usize>| {
+			self.skeleton.sublist_at(node_index).map_or_else(A::empty, |sublist| {
+				sublist.aggregate_by_index(start, end.unwrap_or_else(|| sublist.deep_size()))
+			})
+		};
+		if first == last {
+			return sublist_aggregate(first, start - first_count, Some(end - first_count));
+		}
+		// the links after the one leading to the node after first lead past the sublists of the
+		// nodes before last and the nodes up to last
+		sublist_aggregate(first, start - first_count, None)
+			.combine(&self.value_aggregate(first + 1))
+			.combine(&self.aggregates.aggregate_between(first + 1, last))
+			.combine(&sublist_aggregate(last, 0, Some(end - last_count)))
+	}
+
+	/// Calls `visit` for each of the first `end` nodes, counting the nodes of sublists, for which
+	/// `keep` holds, and returns the aggregate of these nodes combined after `before`.
+	///
+	/// `keep` is called with the aggregate of all nodes before a sequence of nodes, combined after
+	/// `before`, and the aggregate of that sequence, and must hold for a sequence whenever it holds
+	/// for one of its nodes. `visit` is called with the aggregate before the node and its value.
+	/// Sequences for which `keep` doesn't hold aren't descended into, so this takes time logarithmic
+	/// in the number of nodes for every visited node, and at least once.
+	pub(crate) fn visit_nodes_where<K, V>(&self, end: usize, before: A, keep: &K, visit: &mut V) -> A
+		where K: Fn(&A, &A) -> bool, V: FnMut(&A, &T) {
+		let (last, last_count) = self.skeleton.last_node_with_count_up_to_at_most(self.slot_count, end);
+		// the links up to last, of decreasing degree, lead past all nodes up to last
+		let mut before = before;
+		let mut link_end = 0;
+		for degree in (0..usize::BITS).rev() {
+			if last & (1 << degree) != 0 {
+				link_end += 1 << degree;
+				before = self.visit_link_where(link_end - 1, degree, before, keep, visit);
+			}
+		}
+		match self.skeleton.sublist_at(last) {
+			Some(sublist) if end > last_count => sublist.visit_nodes_where(end - last_count, before, keep, visit),
+			_ => before
+		}
+	}
+
+	/// Like [`Self::visit_nodes_where`] for the nodes the link at `link_index` of `degree` leads
+	/// past.
+	fn visit_link_where<K, V>(&self, link_index: usize, degree: u32, before: A, keep: &K, visit: &mut V) -> A
+		where K: Fn(&A, &A) -> bool, V: FnMut(&A, &T) {
+		let aggregate = self.aggregates.aggregate_at(link_index);
+		if !keep(&before, aggregate) {
+			return before.combine(aggregate);
+		}
+		let mut before = (0..degree).rev().fold(before, |before, degree| {
+			self.visit_link_where(link_index - (1 << degree), degree, before, keep, visit)
+		});
+		if let Some(sublist) = self.skeleton.sublist_at(link_index) {
+			before = sublist.visit_nodes_where(sublist.deep_size(), before, keep, visit);
+		}
+		if let Some(value) = self.values.get(link_index).and_then(Option::as_ref) {
+			let aggregate = A::of(value);
+			if keep(&before, &aggregate) {
+				visit(&before, value);
+			}
+			before = before.combine(&aggregate);
+		}
+		before
+	}
+
+	/// The value of the node at `node_index` of this list's skeleton, see
+	/// [`FilledSpacedList::value_at`](crate::spaced_lists::filled::FilledSpacedList::value_at).
+	pub(crate) fn value_at(&self, node_index: usize) -> &T {
+		self.values[node_index - 1].as_ref().expect("Vacant slots have no value")
+	}
+
+	/// The aggregate of the value of the node at `node_index`, which is empty if there is no such
+	/// node or its slot is vacant.
+	fn value_aggregate(&self, node_index: usize) -> A {
+		self.values.get(node_index - 1).and_then(Option::as_ref).map_or_else(A::empty, A::of)
+	}
+
+	pub(crate) fn replace_value_at_path(&mut self, sublist_path: &[usize], node_index: usize, value: T) -> T {
+		match sublist_path.split_first() {
+			None => self.replace_value_at(node_index, value),
+			Some((&sublist_index, sublist_path)) => {
+				let sublist = self.skeleton.sublist_at_mut(sublist_index).unwrap();
+				let value = sublist.replace_value_at_path(sublist_path, node_index, value);
+				self.sublist_changed(sublist_index);
+				value
+			}
+		}
+	}
+
+	/// The aggregate of the sublist of the start node of the link at `link_index`, followed by its
+	/// end node, if these exist.
+	fn link_element_at(&self, link_index: usize) -> A {
+		let sublist = self.skeleton.sublist_at(link_index).map_or_else(A::empty, |sublist| sublist.aggregate());
+		sublist.combine(&self.value_aggregate(link_index + 1))
+	}
+
+	fn update_link_at(&mut self, link_index: usize) {
+		let element = self.link_element_at(link_index);
+		self.aggregates.set_element(link_index, element);
+	}
+}
+
+impl<S: Spacing, T, A: Aggregate<T>> CrateSpacedList<S> for AggregatedSpacedList<S, T, A> {
+	type Value = T;
+
+	fn skeleton(&self) -> &SpacedListSkeleton<S, Self> {
+		&self.skeleton
+	}
+
+	fn skeleton_mut(&mut self) -> &mut SpacedListSkeleton<S, Self> {
+		&mut self.skeleton
+	}
+
+	fn grow(&mut self) {
+		self.skeleton.grow();
+		self.aggregates.grow();
+	}
+
+	fn try_reserve_capacity(&mut self, capacity: usize) -> Result<(), TryReserveError> {
+		self.skeleton.try_reserve_capacity(capacity)?;
+		self.aggregates.try_reserve_capacity(capacity)
+	}
+
+	fn shrink(&mut self) {
+		self.skeleton.shrink();
+		self.aggregates.shrink();
+	}
+
+	fn slot_count(&self) -> usize {
+		self.slot_count
+	}
+
+	fn slot_count_mut(&mut self) -> &mut usize {
+		&mut self.slot_count
+	}
+
+	fn value_count(&self) -> usize {
+		self.values.len()
+	}
+
+	fn add_value_memory_usage(&self, usage: &mut MemoryUsage) {
+		usage.add_vec(&self.values, self.slot_count);
+		self.aggregates.add_memory_usage(self.slot_count, usage);
+	}
+
+	fn try_reserve_values(&mut self, additional: usize) -> Result<(), TryReserveError> {
+		self.values.try_reserve(additional)
+	}
+
+	fn push_value(&mut self, value: T) {
+		self.values.push(Some(value));
+		self.update_link_at(self.slot_count - 1);
+	}
+
+	fn remove_value_at(&mut self, node_index: usize) -> T {
+		let value = self.values.pop().flatten().expect("Vacant slots have no value");
+		self.update_link_at(node_index - 1);
+		value
+	}
+
+	fn vacate_value_at(&mut self, node_index: usize) -> T {
+		let value = self.values[node_index - 1].take().expect("Vacant slots have no value");
+		self.update_link_at(node_index - 1);
+		value
+	}
+
+	fn occupy_value_at(&mut self, node_index: usize, value: T) {
+		self.values[node_index - 1] = Some(value);
+		self.update_link_at(node_index - 1);
+	}
+
+	fn remove_vacant_value_at(&mut self, node_index: usize) {
+		// the link to the vacant slot already led past no node
+		self.values.pop();
+	}
+
+	fn remove_vacant_values(&mut self) {
+		self.values.retain(Option::is_some);
+		let elements = (0..self.skeleton.capacity()).map(|link_index| self.link_element_at(link_index)).collect();
+		self.aggregates = LinkAggregates::from_elements(elements);
+	}
+
+	fn replace_value_at(&mut self, node_index: usize, value: T) -> T {
+		let value = self.values[node_index - 1].replace(value);
+		self.update_link_at(node_index - 1);
+		value.expect("Vacant slots have no value")
+	}
+
+	fn sublist_changed(&mut self, node_index: usize) {
+		self.update_link_at(node_index);
+	}
+}
+
+impl<S: Spacing, T, A: Aggregate<T>> SpacedList<S> for AggregatedSpacedList<S, T, A> {
+	fn length(&self) -> S {
+		self.skeleton.length()
+	}
+
+	fn size(&self) -> usize {
+		self.slot_count - self.skeleton.vacancy_count()
+	}
+
+	fn capacity(&self) -> usize {
+		self.skeleton.capacity()
+	}
+}
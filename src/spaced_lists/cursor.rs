@@ -0,0 +1,280 @@
+use crate::spaced_lists::{SpacedList, Spacing};
+use crate::spaced_lists::skeleton::traversal::Traversal;
+
+/// A cursor that rests on a node of a spaced list (or on node zero) and can edit the list there,
+/// see [`SpacedList::cursor_mut`].
+///
+/// Unlike a traversal, a cursor doesn't borrow the sublists it is in, but remembers the path to
+/// its node, so it stays on the same node across edits.
+pub struct CursorMut<'a, S: Spacing, List: SpacedList<S>> {
+	list: &'a mut List,
+	/// The indices of the nodes whose sublists the cursor is in, starting with the outermost one.
+	sublist_path: Vec<usize>,
+	/// The positions of the nodes at `sublist_path`, which are the positions of node zero of their
+	/// sublists.
+	sublist_positions: Vec<S>,
+	node_index: usize,
+	position: S,
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> CursorMut<'a, S, List> {
+	pub(crate) fn new(list: &'a mut List) -> Self {
+		Self {
+			list,
+			sublist_path: vec![],
+			sublist_positions: vec![],
+			node_index: 0,
+			position: S::zero(),
+		}
+	}
+
+	/// The position of the node the cursor rests on.
+	pub fn position(&self) -> S {
+		self.position
+	}
+
+	/// Whether the cursor rests on node zero, which is at position zero, but is not an actual node
+	/// of the list.
+	pub fn is_at_node_zero(&self) -> bool {
+		self.node_index == 0
+	}
+
+	/// A traversal resting on the same node as this cursor.
+	pub fn traversal(&self) -> Traversal<'_, S, List> {
+		Traversal::at_path(self.list, &self.sublist_path, self.node_index)
+	}
+
+	pub fn has_next(&self) -> bool {
+		// only the last node of the outermost list has no node after it, as the last node of a list
+		// never has a sublist
+		let list = descendant(self.list, &self.sublist_path);
+		list.skeleton().sublist_at(self.node_index).is_some() || self.node_index < list.slot_count()
+			|| !self.sublist_path.is_empty()
+	}
+
+	pub fn has_previous(&self) -> bool {
+		// node zero of a sublist is the node the sublist belongs to
+		let list = descendant(self.list, &self.sublist_path);
+		(self.node_index > 1 && list.skeleton().previous_occupied(self.node_index) > 0)
+			|| !self.sublist_path.is_empty()
+	}
+
+	/// Moves the cursor back to node zero.
+	pub fn reset(&mut self) {
+		self.sublist_path.clear();
+		self.sublist_positions.clear();
+		self.node_index = 0;
+		self.position = S::zero();
+	}
+
+	/// Moves to the next node, see `Traversal::advance`.
+	///
+	/// # Panics
+	///
+	/// Panics if there is no next node.
+	pub fn advance(&mut self) {
+		let mut list = descendant(self.list, &self.sublist_path);
+		if let Some(sublist) = list.skeleton().sublist_at(self.node_index) {
+			self.sublist_path.push(self.node_index);
+			self.sublist_positions.push(self.position);
+			self.position += sublist.skeleton().get_node_position_at(1);
+			self.node_index = sublist.skeleton().next_occupied(0);
+			return;
+		}
+		// the node after the last node of a sublist is the one after the node the sublist belongs to
+		while self.node_index == list.slot_count() {
+			self.node_index = self.sublist_path.pop().expect("Cannot advance past the last node");
+			self.position = self.sublist_positions.pop().unwrap();
+			list = descendant(self.list, &self.sublist_path);
+		}
+		self.position += list.skeleton().get_distance_at(self.node_index);
+		self.node_index = list.skeleton().next_occupied(self.node_index);
+	}
+
+	/// Moves to the previous node, see `Traversal::retreat`.
+	///
+	/// # Panics
+	///
+	/// Panics if the cursor rests on node zero.
+	pub fn retreat(&mut self) {
+		assert!(self.node_index > 0, "Cannot retreat past node zero");
+		let list = descendant(self.list, &self.sublist_path);
+		let previous_index = list.skeleton().previous_occupied(self.node_index);
+		if previous_index == 0 && !self.sublist_path.is_empty() {
+			self.node_index = self.sublist_path.pop().unwrap();
+			self.position = self.sublist_positions.pop().unwrap();
+			return;
+		}
+		self.node_index = previous_index;
+		self.position -= list.skeleton().get_distance_at(self.node_index);
+		if let Some(sublist) = list.skeleton().sublist_at(self.node_index) {
+			// the last node of a sublist never has a sublist itself
+			self.sublist_path.push(self.node_index);
+			self.sublist_positions.push(self.position);
+			self.position += sublist.length();
+			self.node_index = sublist.slot_count();
+		}
+	}
+
+	/// See `Traversal::advance_while`.
+	pub fn advance_while<F: Fn(&Traversal<S, List>) -> bool>(&mut self, condition: F) {
+		self.move_with(|traversal| traversal.advance_while(condition))
+	}
+
+	/// See `Traversal::retreat_while`.
+	pub fn retreat_while<F: Fn(&Traversal<S, List>) -> bool>(&mut self, condition: F) {
+		self.move_with(|traversal| traversal.retreat_while(condition))
+	}
+
+	/// Inserts a node `offset` after the node the cursor rests on, after all nodes that are already
+	/// at that position. The cursor stays on its node.
+	///
+	/// For lists that store values, the new node gets the default value.
+	pub fn insert_node(&mut self, offset: S) where List::Value: Default {
+		self.insert_node_with_value(offset, Default::default())
+	}
+
+	/// Like [`Self::insert_node`], but stores `value` for the new node.
+	///
+	/// A node before the next node of the list the cursor is in is inserted right into the sublist
+	/// of the cursor's node, other nodes are inserted from the outermost list, after which the
+	/// cursor finds its node again by the number of nodes before it.
+	pub fn insert_node_with_value(&mut self, offset: S, value: List::Value) {
+		let position = self.position + offset;
+		if self.is_at_node_zero() {
+			self.list.insert_node_with_value(position, value);
+			return;
+		}
+		if offset >= S::zero() && self.next_shallow_position().is_none_or(|next| position < next) {
+			self.list.insert_after_node_at_path(&self.sublist_path, self.node_index, offset, value);
+			return;
+		}
+		let node_count = self.node_count_up_to();
+		self.list.insert_node_with_value(position, value);
+		if offset < S::zero() {
+			self.move_to_nth(node_count);
+		} else {
+			self.move_to_nth(node_count - 1);
+		}
+	}
+
+	/// Removes the node the cursor rests on and returns its value, keeping all other nodes at their
+	/// positions. The cursor moves to the previous node, or to node zero if there is none.
+	///
+	/// # Panics
+	///
+	/// Panics if the cursor rests on node zero.
+	pub fn remove_node(&mut self) -> List::Value {
+		assert!(!self.is_at_node_zero(), "Cannot remove node zero");
+		let node_count = self.node_count_up_to();
+		let value = self.list.take_node_at_path(&self.sublist_path, self.node_index);
+		// removing a node can move the nodes around it into or out of sublists
+		if node_count > 1 {
+			self.move_to_nth(node_count - 2);
+		} else {
+			self.reset();
+		}
+		value
+	}
+
+	/// Moves all nodes after the node the cursor rests on `amount` further back, lengthening the
+	/// link to the next node. This includes nodes at the same position as the cursor that come
+	/// after its node.
+	pub fn inflate_after(&mut self, amount: S) {
+		if let Some((sublist_path, node_index, _)) = self.next_node() {
+			self.list.inflate_before_node_at_path(&sublist_path, node_index, amount);
+		}
+	}
+
+	/// Moves all nodes after the node the cursor rests on `amount` further forward, shortening the
+	/// link to the next node.
+	///
+	/// # Panics
+	///
+	/// Panics if this would move the next node to before the node the cursor rests on.
+	pub fn deflate_after(&mut self, amount: S) {
+		if let Some((sublist_path, node_index, next_position)) = self.next_node() {
+			assert!(next_position - self.position >= amount, "Cannot deflate past the node after the cursor");
+			self.list.deflate_before_node_at_path(&sublist_path, node_index, amount);
+		}
+	}
+
+	/// The sublist path, node index and position of the node after the cursor's node, if there is
+	/// one.
+	fn next_node(&self) -> Option<(Vec<usize>, usize, S)> {
+		let list = descendant(self.list, &self.sublist_path);
+		let mut sublist_path = self.sublist_path.clone();
+		if let Some(sublist) = list.skeleton().sublist_at(self.node_index) {
+			sublist_path.push(self.node_index);
+			let first_index = sublist.skeleton().next_occupied(0);
+			return Some((sublist_path, first_index, self.position + sublist.skeleton().get_node_position_at(1)));
+		}
+		let next_position = self.next_shallow_position()?;
+		if self.node_index < list.slot_count() {
+			return Some((sublist_path, list.skeleton().next_occupied(self.node_index), next_position));
+		}
+		// the node a sublist belongs to is never the last node of its list
+		let node_index = sublist_path.pop().unwrap();
+		let super_list = descendant(self.list, &sublist_path);
+		Some((sublist_path, super_list.skeleton().next_occupied(node_index), next_position))
+	}
+
+	/// The position of the node after the cursor's node in the list the cursor is in, or of the
+	/// node after that list if the cursor rests on its last node, which is the position nodes
+	/// inserted into the sublist of the cursor's node must stay before.
+	fn next_shallow_position(&self) -> Option<S> {
+		let list = descendant(self.list, &self.sublist_path);
+		if self.node_index < list.slot_count() {
+			return Some(self.position + list.skeleton().get_distance_at(self.node_index));
+		}
+		let (&node_index, super_path) = self.sublist_path.split_last()?;
+		let super_list = descendant(self.list, super_path);
+		Some(*self.sublist_positions.last().unwrap() + super_list.skeleton().get_distance_at(node_index))
+	}
+
+	/// The number of nodes up to and including the cursor's node, counting the nodes of sublists,
+	/// see `Traversal::node_count_up_to`.
+	fn node_count_up_to(&self) -> usize {
+		let mut list = &*self.list;
+		let mut count = 0;
+		for &sublist_index in &self.sublist_path {
+			count += list.skeleton().get_node_count_up_to(sublist_index);
+			list = list.skeleton().sublist_at(sublist_index).unwrap();
+		}
+		count + list.skeleton().get_node_count_up_to(self.node_index)
+	}
+
+	/// Moves the cursor to the node with `index` nodes before it, which must exist, see
+	/// [`SpacedList::nth_node`].
+	fn move_to_nth(&mut self, index: usize) {
+		self.reset();
+		self.move_with(|traversal| traversal.list.nth_node(index).unwrap());
+	}
+
+	fn move_with<F: for<'b> FnOnce(Traversal<'b, S, List>) -> Traversal<'b, S, List>>(&mut self, f: F) {
+		let mut traversal = &f(self.traversal());
+		let (node_index, position) = (traversal.node_index, traversal.position);
+		let (mut sublist_path, mut sublist_positions) = (vec![], vec![]);
+		while let Some(super_traversal) = &traversal.super_traversal {
+			sublist_path.push(super_traversal.node_index);
+			sublist_positions.push(super_traversal.position);
+			traversal = super_traversal;
+		}
+		sublist_path.reverse();
+		sublist_positions.reverse();
+		self.sublist_path = sublist_path;
+		self.sublist_positions = sublist_positions;
+		self.node_index = node_index;
+		self.position = position;
+	}
+}
+
+/// The list reached by descending into the sublists of the nodes at `sublist_path`, one after the
+/// other, see [`CrateSpacedList::descendant_mut`].
+fn descendant<'a, S: 'a + Spacing, List: SpacedList<S>>(list: &'a List, sublist_path: &[usize]) -> &'a List {
+	let mut list = list;
+	for &node_index in sublist_path {
+		list = list.skeleton().sublist_at(node_index).unwrap();
+	}
+	list
+}
@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// The error returned by the fallible (`try_`) methods of spaced lists and traversals.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpacedListError {
+	/// There is no node after the current one to advance to.
+	NoNextNode,
+	/// There is no node before the current one to retreat to.
+	NoPreviousNode,
+	/// An amount of space to insert or remove was negative.
+	NegativeAmount,
+	/// Removing space would move a node to before the position the space was removed after.
+	DeflationPastNode,
+	/// The distance between two positions in the list would not be representable.
+	PositionOverflow,
+	/// The memory needed to add nodes could not be allocated.
+	AllocationFailed,
+}
+
+impl Display for SpacedListError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str(match self {
+			SpacedListError::NoNextNode => "there is no next node",
+			SpacedListError::NoPreviousNode => "there is no previous node",
+			SpacedListError::NegativeAmount => "the amount is negative",
+			SpacedListError::DeflationPastNode => "cannot deflate past the next node",
+			SpacedListError::PositionOverflow => "the distance between two positions would overflow",
+			SpacedListError::AllocationFailed => "memory allocation failed",
+		})
+	}
+}
+
+impl Error for SpacedListError {}
+
+/// A violation of the structural invariants of a spaced list, see [`SpacedList::validate`].
+///
+/// [`SpacedList::validate`]: crate::SpacedList::validate
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvariantViolation {
+	/// The indices of the nodes whose sublists lead to the list the violation was found in,
+	/// starting with the outermost one.
+	pub sublist_path: Vec<usize>,
+	pub kind: ViolationKind,
+}
+
+/// The invariant broken by an [`InvariantViolation`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ViolationKind {
+	/// The numbers of links, node counts and sublist slots differ, or aren't a power of two.
+	InvalidCapacity,
+	/// The size is greater than the capacity, differs from the number of stored values, or the
+	/// number of vacant slots is wrong.
+	InvalidSize,
+	/// The link lengths imply a negative distance from the node at `link_index` to the next one.
+	NegativeDistance { link_index: usize },
+	/// The link lengths imply a non-zero distance from the node at `link_index`, which is the last
+	/// node or after it, to the next one.
+	DistanceAfterLastNode { link_index: usize },
+	/// The number of nodes the link at `link_index` leads past doesn't match the number of nodes in
+	/// the sublist of its start node plus its end node.
+	InvalidNodeCount { link_index: usize },
+	/// The largest gap stored for the link at `link_index` doesn't match the largest distance
+	/// between neighbouring nodes from its start node to its end node.
+	InvalidLargestGap { link_index: usize },
+	/// The node at `node_index` is node zero, the last node or after it, but has a sublist.
+	MisplacedSublist { node_index: usize },
+	/// The sublist of the node at `node_index` is empty.
+	EmptySublist { node_index: usize },
+	/// The sublist of the node at `node_index` has nodes before that node or after the next one.
+	SublistOutOfBounds { node_index: usize },
+	/// The slot of the node at `node_index` is vacant, but is the slot of node zero, the last slot
+	/// or after it, has a sublist or isn't at the position of the next node.
+	InvalidVacancy { node_index: usize },
+}
+
+impl Display for InvariantViolation {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{:?} in the list at sublist path {:?}", self.kind, self.sublist_path)
+	}
+}
+
+impl Error for InvariantViolation {}
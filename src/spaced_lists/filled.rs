@@ -0,0 +1,215 @@
+use std::collections::TryReserveError;
+use std::ops::{Index, IndexMut, RangeBounds};
+
+use crate::spaced_lists::{CrateSpacedList, SpacedList, Spacing};
+use crate::spaced_lists::memory::MemoryUsage;
+use crate::spaced_lists::skeleton::SpacedListSkeleton;
+use crate::spaced_lists::iter::Nodes;
+
+/// A spaced list that stores a value for every node.
+pub struct FilledSpacedList<S: Spacing, T> {
+	skeleton: SpacedListSkeleton<S, Self>,
+	slot_count: usize,
+	/// The values of the nodes in every slot after node zero, `None` for vacant slots.
+	values: Vec<Option<T>>,
+}
+
+impl<S: Spacing, T> Default for FilledSpacedList<S, T> {
+	fn default() -> Self {
+		Self {
+			skeleton: Default::default(),
+			slot_count: 0,
+			values: vec![],
+		}
+	}
+}
+
+impl<S: Spacing, T> FilledSpacedList<S, T> {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Creates a list that can hold at least `capacity` nodes without growing, unless they are
+	/// inserted in a way that nests them in sublists.
+	pub fn with_capacity(capacity: usize) -> Self {
+		let mut list = Self::new();
+		list.reserve(capacity);
+		list
+	}
+
+	/// Appends a node at `distance` after the last node.
+	pub fn append(&mut self, distance: S, value: T) {
+		self.append_node_with_value(distance, value)
+	}
+
+	/// Inserts a node at `position`, after all nodes that are already at that position.
+	pub fn insert(&mut self, position: S, value: T) {
+		self.insert_node_with_value(position, value)
+	}
+
+	/// The value of the node at `position`.
+	///
+	/// If there are multiple nodes at `position`, this returns the value of the last one.
+	pub fn get(&self, position: S) -> Option<&T> {
+		self.node_at(position).map(|traversal| traversal.list.value_at(traversal.node_index))
+	}
+
+	/// The value of the node at `position`.
+	///
+	/// If there are multiple nodes at `position`, this returns the value of the last one.
+	pub fn get_mut(&mut self, position: S) -> Option<&mut T> {
+		let traversal = self.node_at(position)?;
+		let path = traversal.sublist_path();
+		let node_index = traversal.node_index;
+		Some(self.descendant_mut(&path).value_at_mut(node_index))
+	}
+
+	/// Removes the node at `position` and returns its value, keeping all other nodes at their
+	/// positions.
+	///
+	/// If there are multiple nodes at `position`, the last one is removed.
+	pub fn remove(&mut self, position: S) -> Option<T> {
+		self.take_node_at(position)
+	}
+
+	/// The value of the node with `index` nodes before it, or `None` if there are not that many
+	/// nodes.
+	pub fn get_by_index(&self, index: usize) -> Option<&T> {
+		self.nth_node(index).map(|traversal| traversal.list.value_at(traversal.node_index))
+	}
+
+	/// The value of the node with `index` nodes before it, or `None` if there are not that many
+	/// nodes.
+	pub fn get_by_index_mut(&mut self, index: usize) -> Option<&mut T> {
+		let traversal = self.nth_node(index)?;
+		let path = traversal.sublist_path();
+		let node_index = traversal.node_index;
+		Some(self.descendant_mut(&path).value_at_mut(node_index))
+	}
+
+	/// Iterates over the positions and values of all nodes within `bounds` in order.
+	///
+	/// # Panics
+	///
+	/// Panics if the start of `bounds` is greater than its end, or if they are equal and both
+	/// excluded.
+	pub fn range_with_values<R: RangeBounds<S>>(&self, bounds: R)
+		-> impl DoubleEndedIterator<Item = (S, &T)> {
+		Nodes::in_range(self, bounds)
+			.map(|traversal| (traversal.position, traversal.list.value_at(traversal.node_index)))
+	}
+
+	/// The value of the node at `node_index` of this list's skeleton.
+	///
+	/// # Panics
+	///
+	/// Panics if `node_index` is zero, greater than the slot count of this list or the index of a
+	/// vacant slot.
+	pub(crate) fn value_at(&self, node_index: usize) -> &T {
+		self.values[node_index - 1].as_ref().expect("Vacant slots have no value")
+	}
+
+	/// See [`Self::value_at`].
+	fn value_at_mut(&mut self, node_index: usize) -> &mut T {
+		self.values[node_index - 1].as_mut().expect("Vacant slots have no value")
+	}
+}
+
+impl<S: Spacing, T> CrateSpacedList<S> for FilledSpacedList<S, T> {
+	type Value = T;
+
+	fn skeleton(&self) -> &SpacedListSkeleton<S, Self> {
+		&self.skeleton
+	}
+
+	fn skeleton_mut(&mut self) -> &mut SpacedListSkeleton<S, Self> {
+		&mut self.skeleton
+	}
+
+	fn slot_count(&self) -> usize {
+		self.slot_count
+	}
+
+	fn slot_count_mut(&mut self) -> &mut usize {
+		&mut self.slot_count
+	}
+
+	fn value_count(&self) -> usize {
+		self.values.len()
+	}
+
+	fn add_value_memory_usage(&self, usage: &mut MemoryUsage) {
+		usage.add_vec(&self.values, self.slot_count);
+	}
+
+	fn try_reserve_values(&mut self, additional: usize) -> Result<(), TryReserveError> {
+		self.values.try_reserve(additional)
+	}
+
+	fn push_value(&mut self, value: T) {
+		self.values.push(Some(value))
+	}
+
+	fn remove_value_at(&mut self, node_index: usize) -> T {
+		self.values.pop().flatten().expect("Vacant slots have no value")
+	}
+
+	fn vacate_value_at(&mut self, node_index: usize) -> T {
+		self.values[node_index - 1].take().expect("Vacant slots have no value")
+	}
+
+	fn occupy_value_at(&mut self, node_index: usize, value: T) {
+		self.values[node_index - 1] = Some(value);
+	}
+
+	fn remove_vacant_value_at(&mut self, node_index: usize) {
+		self.values.pop();
+	}
+
+	fn remove_vacant_values(&mut self) {
+		self.values.retain(Option::is_some);
+	}
+
+	fn replace_value_at(&mut self, node_index: usize, value: T) -> T {
+		std::mem::replace(self.value_at_mut(node_index), value)
+	}
+}
+
+impl<S: Spacing, T> SpacedList<S> for FilledSpacedList<S, T> {
+	fn length(&self) -> S {
+		self.skeleton.length()
+	}
+
+	fn size(&self) -> usize {
+		self.slot_count - self.skeleton.vacancy_count()
+	}
+
+	fn capacity(&self) -> usize {
+		self.skeleton.capacity()
+	}
+}
+
+impl<S: Spacing, T> Index<usize> for FilledSpacedList<S, T> {
+	type Output = T;
+
+	/// The value of the node with `index` nodes before it, see [`FilledSpacedList::get_by_index`].
+	///
+	/// # Panics
+	///
+	/// Panics if there are not that many nodes.
+	fn index(&self, index: usize) -> &T {
+		self.get_by_index(index).expect("Index out of bounds")
+	}
+}
+
+impl<S: Spacing, T> IndexMut<usize> for FilledSpacedList<S, T> {
+	/// The value of the node with `index` nodes before it, see
+	/// [`FilledSpacedList::get_by_index_mut`].
+	///
+	/// # Panics
+	///
+	/// Panics if there are not that many nodes.
+	fn index_mut(&mut self, index: usize) -> &mut T {
+		self.get_by_index_mut(index).expect("Index out of bounds")
+	}
+}
@@ -0,0 +1,158 @@
+use std::ops::Range;
+
+use num_traits::CheckedAdd;
+
+use crate::spaced_lists::{CrateSpacedList, SpacedList, Spacing};
+use crate::spaced_lists::filled::FilledSpacedList;
+use crate::spaced_lists::skeleton::traversal::Traversal;
+
+/// The value of a node of a [`FilledRangeSpacedList`].
+pub(crate) enum RangeNode<T> {
+	Start(T),
+	End,
+}
+
+/// A spaced list of non-overlapping, non-empty ranges that stores a value for every range.
+///
+/// Every range is represented by a node at its start, which holds the value, and a node at its
+/// end, so the nodes of the underlying list alternate between range starts and range ends.
+pub struct FilledRangeSpacedList<S: Spacing, T> {
+	list: FilledSpacedList<S, RangeNode<T>>,
+	size: usize,
+}
+
+impl<S: Spacing, T> Default for FilledRangeSpacedList<S, T> {
+	fn default() -> Self {
+		Self {
+			list: Default::default(),
+			size: 0,
+		}
+	}
+}
+
+impl<S: Spacing, T> FilledRangeSpacedList<S, T> {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// The number of ranges in this list.
+	pub fn size(&self) -> usize {
+		self.size
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.size == 0
+	}
+
+	/// The end of the last range.
+	pub fn length(&self) -> S {
+		self.list.length()
+	}
+
+	/// Inserts `range`, which must neither be empty nor overlap any other range of this list.
+	///
+	/// # Panics
+	///
+	/// Panics if `range` is empty or overlaps another range.
+	pub fn insert_range(&mut self, range: Range<S>, value: T) {
+		assert!(range.start < range.end, "Cannot insert an empty range");
+		if let Some(traversal) = self.list.node_at_or_before(range.start) {
+			assert!(matches!(traversal.list.value_at(traversal.node_index), RangeNode::End),
+			        "Cannot insert a range that overlaps another range");
+		}
+		if let Some(traversal) = self.list.node_after(range.start) {
+			assert!(traversal.position >= range.end, "Cannot insert a range that overlaps another range");
+		}
+		self.list.insert(range.start, RangeNode::Start(value));
+		// the end must come before the start of a range that starts where this one ends
+		self.list.insert_node_in_front_with_value(range.end, RangeNode::End);
+		self.size += 1;
+	}
+
+	/// The range containing `position` (including its start, excluding its end) and its value.
+	pub fn range_containing(&self, position: S) -> Option<(Range<S>, &T)> {
+		let start = self.list.node_at_or_before(position)?;
+		match start.list.value_at(start.node_index) {
+			RangeNode::Start(value) => Some((start.position..start.clone().advance().position, value)),
+			RangeNode::End => None
+		}
+	}
+
+	/// Removes the range containing `position` (including its start, excluding its end) and
+	/// returns it together with its value.
+	pub fn remove_range_containing(&mut self, position: S) -> Option<(Range<S>, T)> {
+		let range = self.range_containing(position)?.0;
+		// there might be another range starting where this one ends, so the end node is removed
+		// first, using its exact location
+		let end = self.list.node_after(range.start).unwrap();
+		let (sublist_path, node_index) = (end.sublist_path(), end.node_index);
+		self.list.take_node_at_path(&sublist_path, node_index);
+		let value = match self.list.remove(range.start) {
+			Some(RangeNode::Start(value)) => value,
+			_ => unreachable!()
+		};
+		self.size -= 1;
+		Some((range, value))
+	}
+
+	/// Moves all range starts and ends after `position` `amount` further back. The range
+	/// containing `position`, if there is one, grows by `amount`.
+	pub fn inflate_after(&mut self, position: S, amount: S) {
+		self.list.inflate_after(position, amount)
+	}
+
+	/// Moves all range starts and ends after `position` `amount` further forward. The range
+	/// containing `position`, if there is one, shrinks by `amount`.
+	///
+	/// # Panics
+	///
+	/// Panics if this would move a range start or end to before `position`, or make a range empty.
+	pub fn deflate_after(&mut self, position: S, amount: S) where S: CheckedAdd {
+		if let Some((range, _)) = self.range_containing(position) {
+			assert!(
+				range.start.checked_add(&amount).is_some_and(|end| end < range.end),
+				"Cannot deflate a range until it is empty"
+			);
+		}
+		self.list.deflate_after(position, amount)
+	}
+
+	/// Iterates over all ranges and their values, in order.
+	pub fn iter(&self) -> Ranges<'_, S, T> {
+		Ranges {
+			traversal: Some(self.list.traversal()),
+			remaining: self.size,
+		}
+	}
+}
+
+/// Iterator over the ranges of a [`FilledRangeSpacedList`], see
+/// [`FilledRangeSpacedList::iter`].
+pub struct Ranges<'a, S: Spacing, T> {
+	traversal: Option<Traversal<'a, S, FilledSpacedList<S, RangeNode<T>>>>,
+	remaining: usize,
+}
+
+impl<'a, S: Spacing, T> Iterator for Ranges<'a, S, T> {
+	type Item = (Range<S>, &'a T);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		let start = self.traversal.take().unwrap().advance();
+		let value = match start.list.value_at(start.node_index) {
+			RangeNode::Start(value) => value,
+			RangeNode::End => unreachable!()
+		};
+		let end = start.clone().advance();
+		let range = start.position..end.position;
+		self.traversal = Some(end);
+		Some((range, value))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
@@ -1,24 +1,28 @@
-use std::default::default;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
-use std::marker::PhantomData;
-
-use num_traits::{Zero, zero};
-
 use crate::spaced_lists::{CrateSpacedList, SpacedList, Spacing};
 use crate::spaced_lists::skeleton::SpacedListSkeleton;
-use crate::spaced_lists::skeleton::traversal::Traversal;
+use crate::spaced_lists::iter::Iter;
+
+/// What to do with positions that occur multiple times when creating a list from unsorted
+/// positions, see [`HollowSpacedList::from_unsorted`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DuplicatePolicy {
+	/// Add a node for every occurrence of a position.
+	Keep,
+	/// Add a single node for every position, no matter how often it occurs.
+	Remove,
+}
 
 pub struct HollowSpacedList<S: Spacing> {
 	skeleton: SpacedListSkeleton<S, Self>,
-	size: usize,
+	slot_count: usize,
 	super_list: Option<Box<Self>>
 }
 
 impl<S: Spacing> Default for HollowSpacedList<S> {
 	fn default() -> Self {
 		Self {
-			skeleton: default(),
-			size: 0,
+			skeleton: Default::default(),
+			slot_count: 0,
 			super_list: None,
 		}
 	}
@@ -26,11 +30,55 @@ impl<S: Spacing> Default for HollowSpacedList<S> {
 
 impl<S: Spacing> HollowSpacedList<S> {
 	pub fn new() -> Self {
-		default()
+		Default::default()
+	}
+
+	/// Creates a list that can hold at least `capacity` nodes without growing, unless they are
+	/// inserted in a way that nests them in sublists.
+	pub fn with_capacity(capacity: usize) -> Self {
+		let mut list = Self::new();
+		list.reserve(capacity);
+		list
+	}
+
+	/// Creates a list with nodes at `positions`, which must be sorted.
+	///
+	/// Unlike inserting the nodes one by one, this takes linear time, as the capacity is allocated
+	/// up front and the links of all degrees are calculated in a single pass.
+	///
+	/// # Panics
+	///
+	/// Panics if `positions` are not sorted.
+	pub fn from_sorted_positions<I: IntoIterator<Item = S>>(positions: I) -> Self {
+		let positions = positions.into_iter().collect::<Vec<_>>();
+		assert!(positions.windows(2).all(|pair| pair[0] <= pair[1]), "Positions must be sorted");
+		let list = Self {
+			skeleton: SpacedListSkeleton::from_sorted_positions(&positions),
+			slot_count: positions.len(),
+			super_list: None,
+		};
+		list.check_invariants();
+		list
+	}
+
+	/// Creates a list with nodes at `positions`, which may be in any order, handling positions that
+	/// occur multiple times according to `duplicates`.
+	///
+	/// The positions are sorted before the list is built, so unlike inserting them one by one,
+	/// this doesn't nest nodes in sublists, see [`Self::from_sorted_positions`].
+	pub fn from_unsorted<I: IntoIterator<Item = S>>(positions: I, duplicates: DuplicatePolicy) -> Self {
+		let mut positions = positions.into_iter().collect::<Vec<_>>();
+		positions.sort_unstable();
+		if duplicates == DuplicatePolicy::Remove {
+			positions.dedup();
+		}
+		Self::from_sorted_positions(positions)
 	}
 }
 
 impl<S: Spacing> CrateSpacedList<S> for HollowSpacedList<S> {
+	type Value = ();
+
 	fn skeleton(&self) -> &SpacedListSkeleton<S, Self> {
 		&self.skeleton
 	}
@@ -39,9 +87,27 @@ impl<S: Spacing> CrateSpacedList<S> for HollowSpacedList<S> {
 		&mut self.skeleton
 	}
 
-	fn size_mut(&mut self) -> &mut usize {
-		&mut self.size
+	fn slot_count(&self) -> usize {
+		self.slot_count
 	}
+
+	fn slot_count_mut(&mut self) -> &mut usize {
+		&mut self.slot_count
+	}
+
+	fn value_count(&self) -> usize {
+		self.slot_count
+	}
+
+	fn push_value(&mut self, value: ()) {}
+
+	fn remove_value_at(&mut self, node_index: usize) {}
+
+	fn vacate_value_at(&mut self, node_index: usize) {}
+
+	fn occupy_value_at(&mut self, node_index: usize, value: ()) {}
+
+	fn replace_value_at(&mut self, node_index: usize, value: ()) {}
 }
 
 impl<S: Spacing> SpacedList<S> for HollowSpacedList<S> {
@@ -50,10 +116,60 @@ impl<S: Spacing> SpacedList<S> for HollowSpacedList<S> {
 	}
 
 	fn size(&self) -> usize {
-		self.size
+		self.slot_count - self.skeleton.vacancy_count()
 	}
 
 	fn capacity(&self) -> usize {
 		self.skeleton.capacity()
 	}
 }
+
+impl<'a, S: Spacing> IntoIterator for &'a HollowSpacedList<S> {
+	type Item = S;
+	type IntoIter = Iter<'a, S, HollowSpacedList<S>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<S: Spacing> Extend<S> for HollowSpacedList<S> {
+	/// Inserts nodes at the given positions, which may be in any order, like
+	/// [`SpacedList::insert_node`] does for a single position.
+	///
+	/// If all positions are at or after the last node, the nodes are appended. Otherwise, the list
+	/// is rebuilt from the merged positions (see [`HollowSpacedList::from_sorted_positions`]),
+	/// which takes time linear in its size, but leaves no nodes nested in sublists.
+	fn extend<I: IntoIterator<Item = S>>(&mut self, positions: I) {
+		let mut positions = positions.into_iter().collect::<Vec<_>>();
+		if positions.is_empty() {
+			return;
+		}
+		positions.sort_unstable();
+		if self.is_empty() || positions[0] >= self.length() {
+			for position in positions {
+				self.append_node(position - self.length());
+			}
+			return;
+		}
+		let mut merged = Vec::with_capacity(self.deep_size() + positions.len());
+		let mut positions = positions.into_iter().peekable();
+		for existing in self.iter() {
+			// new positions go after the existing ones at the same position
+			while let Some(position) = positions.next_if(|&position| position < existing) {
+				merged.push(position);
+			}
+			merged.push(existing);
+		}
+		merged.extend(positions);
+		*self = Self::from_sorted_positions(merged);
+	}
+}
+
+impl<S: Spacing> FromIterator<S> for HollowSpacedList<S> {
+	/// Creates a list with a node at every one of the given positions, which may be in any order,
+	/// see [`HollowSpacedList::from_unsorted`].
+	fn from_iter<I: IntoIterator<Item = S>>(positions: I) -> Self {
+		Self::from_unsorted(positions, DuplicatePolicy::Keep)
+	}
+}
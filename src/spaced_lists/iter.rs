@@ -0,0 +1,153 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::spaced_lists::{SpacedList, Spacing};
+use crate::spaced_lists::skeleton::traversal::Traversal;
+
+/// Iterator over the positions of the nodes of a spaced list, see [`SpacedList::iter`].
+pub struct Iter<'a, S: Spacing, List: SpacedList<S>> {
+	/// The node before the next one to be returned from the front
+	front: Option<Traversal<'a, S, List>>,
+	/// The next node to be returned from the back
+	back: Option<Traversal<'a, S, List>>,
+	remaining: usize,
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> Iter<'a, S, List> {
+	pub(crate) fn new(list: &'a List) -> Self {
+		Self {
+			front: Some(list.traversal()),
+			back: Some(list.traversal().advance_while(|_| true)),
+			remaining: list.deep_size(),
+		}
+	}
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> Iterator for Iter<'a, S, List> {
+	type Item = S;
+
+	fn next(&mut self) -> Option<S> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		let front = self.front.take().unwrap().advance();
+		let position = front.position;
+		self.front = Some(front);
+		Some(position)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> DoubleEndedIterator for Iter<'a, S, List> {
+	fn next_back(&mut self) -> Option<S> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		let back = self.back.take().unwrap();
+		let position = back.position;
+		if self.remaining > 0 {
+			self.back = Some(back.retreat());
+		}
+		Some(position)
+	}
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> ExactSizeIterator for Iter<'a, S, List> {}
+
+/// Double-ended iterator over the nodes of a spaced list between two nodes, yielding traversals
+/// resting on them.
+pub(crate) struct Nodes<'a, S: Spacing, List: SpacedList<S>> {
+	/// The node before the next one to be returned from the front
+	front: Traversal<'a, S, List>,
+	/// The next node to be returned from the back
+	back: Option<Traversal<'a, S, List>>,
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> Nodes<'a, S, List> {
+	/// Creates an iterator over the nodes in `bounds`, mirroring [`BTreeMap::range`].
+	///
+	/// # Panics
+	///
+	/// Panics if the start of `bounds` is greater than its end, or if they are equal and both
+	/// excluded.
+	///
+	/// [`BTreeMap::range`]: std::collections::BTreeMap::range
+	pub(crate) fn in_range<R: RangeBounds<S>>(list: &'a List, bounds: R) -> Self {
+		match (bounds.start_bound(), bounds.end_bound()) {
+			(Bound::Included(start) | Bound::Excluded(start), Bound::Included(end) | Bound::Excluded(end))
+			if start > end => panic!("Range start is greater than range end"),
+			(Bound::Excluded(start), Bound::Excluded(end)) if start == end =>
+				panic!("Range start and end are equal and excluded"),
+			_ => {}
+		}
+		let front = match bounds.start_bound() {
+			Bound::Included(&start) => list.node_before(start),
+			Bound::Excluded(&start) => list.node_at_or_before(start),
+			Bound::Unbounded => None
+		}.unwrap_or_else(|| list.traversal());
+		let back = match bounds.end_bound() {
+			Bound::Included(&end) => list.node_at_or_before(end),
+			Bound::Excluded(&end) => list.node_before(end),
+			Bound::Unbounded => Some(list.traversal().advance_while(|_| true)).filter(|_| !list.is_empty())
+		}.filter(|back| bounds.contains(&back.position));
+		Self { front, back }
+	}
+}
+
+fn is_same_node<S: Spacing, List: SpacedList<S>>(a: &Traversal<S, List>, b: &Traversal<S, List>) -> bool {
+	std::ptr::eq(a.list, b.list) && a.node_index == b.node_index
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> Iterator for Nodes<'a, S, List> {
+	type Item = Traversal<'a, S, List>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let back = self.back.as_ref()?;
+		self.front = self.front.clone().advance();
+		if is_same_node(&self.front, back) {
+			self.back = None;
+		}
+		Some(self.front.clone())
+	}
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> DoubleEndedIterator for Nodes<'a, S, List> {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		let back = self.back.take()?;
+		let previous = back.clone().retreat();
+		if !is_same_node(&previous, &self.front) {
+			self.back = Some(previous);
+		}
+		Some(back)
+	}
+}
+
+/// Iterator over the positions of the nodes of a spaced list within some bounds, see
+/// [`SpacedList::range`].
+pub struct Range<'a, S: Spacing, List: SpacedList<S>> {
+	nodes: Nodes<'a, S, List>,
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> Range<'a, S, List> {
+	pub(crate) fn new<R: RangeBounds<S>>(list: &'a List, bounds: R) -> Self {
+		Self { nodes: Nodes::in_range(list, bounds) }
+	}
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> Iterator for Range<'a, S, List> {
+	type Item = S;
+
+	fn next(&mut self) -> Option<S> {
+		self.nodes.next().map(|traversal| traversal.position)
+	}
+}
+
+impl<'a, S: Spacing, List: SpacedList<S>> DoubleEndedIterator for Range<'a, S, List> {
+	fn next_back(&mut self) -> Option<S> {
+		self.nodes.next_back().map(|traversal| traversal.position)
+	}
+}
@@ -0,0 +1,52 @@
+use std::mem::size_of;
+
+/// A breakdown of the heap memory used by a spaced list and its sublists, see
+/// [`SpacedList::memory_usage`].
+///
+/// The bytes of a sublist itself, not counting its own allocations, are part of the skeleton bytes
+/// of the list containing it, as sublists are stored inline.
+///
+/// [`SpacedList::memory_usage`]: crate::SpacedList::memory_usage
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MemoryUsage {
+	/// The bytes allocated by the list itself, for its link lengths, node counts, sublists and
+	/// values.
+	pub skeleton_bytes: usize,
+	/// The bytes allocated by all sublists, recursively.
+	pub sublist_bytes: usize,
+	/// The part of the skeleton and sublist bytes that is allocated for links and values no node
+	/// uses, which [`SpacedList::shrink_to_fit`] frees as far as possible.
+	///
+	/// [`SpacedList::shrink_to_fit`]: crate::SpacedList::shrink_to_fit
+	pub wasted_capacity_bytes: usize,
+	/// The number of nodes at every nesting depth, starting with the nodes of the list itself, then
+	/// the ones of its sublists and so on. Empty for an empty list.
+	pub depth_histogram: Vec<usize>,
+}
+
+impl MemoryUsage {
+	/// The bytes allocated by the list and all of its sublists.
+	pub fn total_bytes(&self) -> usize {
+		self.skeleton_bytes + self.sublist_bytes
+	}
+
+	/// Adds the bytes allocated by `vec` to the skeleton bytes, counting the ones beyond its first
+	/// `used` elements as wasted.
+	pub(crate) fn add_vec<T>(&mut self, vec: &Vec<T>, used: usize) {
+		self.skeleton_bytes += vec.capacity() * size_of::<T>();
+		self.wasted_capacity_bytes += vec.capacity().saturating_sub(used) * size_of::<T>();
+	}
+
+	/// Adds the memory usage of a sublist, whose nodes are one level deeper than the ones of this
+	/// list.
+	pub(crate) fn add_sublist(&mut self, sublist: MemoryUsage) {
+		self.sublist_bytes += sublist.total_bytes();
+		self.wasted_capacity_bytes += sublist.wasted_capacity_bytes;
+		if self.depth_histogram.len() <= sublist.depth_histogram.len() {
+			self.depth_histogram.resize(sublist.depth_histogram.len() + 1, 0);
+		}
+		for (depth, count) in sublist.depth_histogram.into_iter().enumerate() {
+			self.depth_histogram[depth + 1] += count;
+		}
+	}
+}
@@ -1,25 +1,41 @@
-use std::default::default;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::ops::{Add, AddAssign, RangeBounds, Sub, SubAssign};
 
-use num_traits::{Zero, zero};
+use num_traits::{CheckedAdd, CheckedSub, Zero, zero};
 use crate::spaced_lists::crate_spaced_list::CrateSpacedList;
 
-use crate::spaced_lists::skeleton::SpacedListSkeleton;
 use crate::spaced_lists::skeleton::traversal::Traversal;
+use crate::spaced_lists::iter::{Iter, Range};
+use crate::spaced_lists::cursor::CursorMut;
+use crate::spaced_lists::error::{InvariantViolation, SpacedListError};
+use crate::spaced_lists::memory::MemoryUsage;
 
-pub trait Spacing = Add<Output = Self> + AddAssign + Sub<Output = Self> + SubAssign + Zero + Ord + Copy;
+/// The type of the positions of nodes and the distances between them.
+///
+/// This is implemented for all types that support the necessary arithmetic and comparisons.
+pub trait Spacing: Add<Output = Self> + AddAssign + Sub<Output = Self> + SubAssign + Zero + Ord + Copy {}
+
+impl<T: Add<Output = T> + AddAssign + Sub<Output = T> + SubAssign + Zero + Ord + Copy> Spacing for T {}
 
 pub(crate) mod crate_spaced_list {
+	use std::collections::TryReserveError;
+
+	use num_traits::zero;
+
 	use crate::spaced_lists::skeleton::SpacedListSkeleton;
 	use crate::spaced_lists::skeleton::traversal::Traversal;
 	use crate::spaced_lists::{SpacedList, Spacing};
+	use crate::spaced_lists::error::{InvariantViolation, SpacedListError, ViolationKind};
+	use crate::spaced_lists::memory::MemoryUsage;
 
 	pub trait CrateSpacedList<S: Spacing>: Default {
+		/// The value stored for each node, `()` for lists that don't store any values.
+		type Value;
+
 		fn skeleton(&self) -> &SpacedListSkeleton<S, Self>;
 
 		fn skeleton_mut(&mut self) -> &mut SpacedListSkeleton<S, Self>;
 
-		fn traversal(&self) -> Traversal<S, Self> where Self: SpacedList<S> {
+		fn traversal(&self) -> Traversal<'_, S, Self> where Self: SpacedList<S> {
 			Traversal::new(self)
 		}
 
@@ -27,7 +43,598 @@ pub(crate) mod crate_spaced_list {
 			self.skeleton_mut().grow()
 		}
 
-		fn size_mut(&mut self) -> &mut usize;
+		/// Reserves the memory needed to [grow](Self::grow) up to `capacity`, see
+		/// [`SpacedListSkeleton::try_reserve_capacity`].
+		fn try_reserve_capacity(&mut self, capacity: usize) -> Result<(), TryReserveError> {
+			self.skeleton_mut().try_reserve_capacity(capacity)
+		}
+
+		/// Reserves the memory needed to store `additional` more values, for lists that store
+		/// values.
+		fn try_reserve_values(&mut self, additional: usize) -> Result<(), TryReserveError> {
+			Ok(())
+		}
+
+		/// Halves the capacity, see [`SpacedListSkeleton::shrink`]. The size must be at most half the
+		/// capacity.
+		fn shrink(&mut self) {
+			self.skeleton_mut().shrink()
+		}
+
+		/// Halves the capacity for as long as at most a quarter of it is used, so that removing nodes
+		/// eventually frees memory, without shrinking and growing again when nodes are removed and
+		/// added in turn. Vacant slots are removed first, see [`Self::remove_vacancies`].
+		fn shrink_if_sparse(&mut self) where Self: SpacedList<S> {
+			if self.capacity() > 0 && self.size() <= self.capacity() / 4 {
+				self.remove_vacancies();
+			}
+			while self.capacity() > 0 && self.size() <= self.capacity() / 4 {
+				self.shrink();
+			}
+		}
+
+		/// The number of slots of this list's skeleton in use, which are the ones of its nodes and
+		/// the vacant ones between them, see [`SpacedListSkeleton::vacate_at`].
+		fn slot_count(&self) -> usize;
+
+		fn slot_count_mut(&mut self) -> &mut usize;
+
+		/// Moves all nodes of this list's skeleton into the vacant slots before them (see
+		/// [`SpacedListSkeleton::remove_vacancies`]), which takes time linear in the capacity.
+		fn remove_vacancies(&mut self) where Self: SpacedList<S> {
+			let vacancy_count = self.skeleton().vacancy_count();
+			if vacancy_count == 0 {
+				return;
+			}
+			let slot_count = self.slot_count();
+			self.skeleton_mut().remove_vacancies(slot_count);
+			*self.slot_count_mut() -= vacancy_count;
+			self.remove_vacant_values();
+			self.check_invariants();
+		}
+
+		/// The lowest and the highest of zero and the positions of all nodes, all distances stored
+		/// in the list lie between them.
+		fn position_bounds(&self) -> (S, S) where Self: SpacedList<S> {
+			if self.is_empty() {
+				(zero(), zero())
+			} else {
+				(self.skeleton().get_node_position_at(1).min(zero()), self.length().max(zero()))
+			}
+		}
+
+		/// The number of values stored, which equals the slot count of lists that store values.
+		fn value_count(&self) -> usize;
+
+		/// Adds the memory allocated for values and any other data stored next to the skeleton to
+		/// `usage`, see [`SpacedList::memory_usage`].
+		fn add_value_memory_usage(&self, usage: &mut MemoryUsage) {}
+
+		/// Stores `value` for the node that was just appended.
+		fn push_value(&mut self, value: Self::Value);
+
+		/// Removes the value of the node at `node_index`, which is in the last slot.
+		fn remove_value_at(&mut self, node_index: usize) -> Self::Value;
+
+		/// Takes the value of the node at `node_index`, whose slot was just vacated.
+		fn vacate_value_at(&mut self, node_index: usize) -> Self::Value;
+
+		/// Stores `value` for the node that was just put into the vacant slot at `node_index`.
+		fn occupy_value_at(&mut self, node_index: usize, value: Self::Value);
+
+		/// Removes the entry of the vacant slot at `node_index`, which is the last slot.
+		fn remove_vacant_value_at(&mut self, node_index: usize) {}
+
+		/// Removes the entries of all vacant slots, after the nodes were moved into them by
+		/// [`Self::remove_vacancies`].
+		fn remove_vacant_values(&mut self) {}
+
+		/// Replaces the value of the node at `node_index`, returning the previous one.
+		fn replace_value_at(&mut self, node_index: usize, value: Self::Value) -> Self::Value;
+
+		/// Called after nodes were added to or removed from the sublist of the node at `node_index`,
+		/// including when the sublist was created or removed.
+		fn sublist_changed(&mut self, node_index: usize) {}
+
+		/// The list reached by descending into the sublists of the nodes at `node_indices`, one
+		/// after the other.
+		///
+		/// # Panics
+		///
+		/// Panics if one of these nodes has no sublist.
+		fn descendant_mut<'a>(&'a mut self, node_indices: &[usize]) -> &'a mut Self where S: 'a {
+			let mut list = self;
+			for &node_index in node_indices {
+				list = list.skeleton_mut().sublist_at_mut(node_index).unwrap();
+			}
+			list
+		}
+
+		fn append_node_with_value(&mut self, distance: S, value: Self::Value) where Self: SpacedList<S> {
+			if self.is_full() {
+				self.grow()
+			}
+
+			let slot_count = self.slot_count();
+			self.skeleton_mut().inflate_at(slot_count, distance);
+			self.skeleton_mut().add_nodes_at(slot_count, 1);
+			*self.slot_count_mut() += 1;
+			self.push_value(value);
+			self.check_invariants();
+		}
+
+		fn insert_node_with_value(&mut self, position: S, value: Self::Value) where Self: SpacedList<S> {
+			if self.is_empty() || position >= self.length() {
+				self.append_node_with_value(position - self.length(), value)
+			} else if position < self.skeleton().get_node_position_at(1) {
+				self.prepend_node(position, value)
+			} else {
+				let mut traversal = self.traversal();
+				traversal.advance_while_shallow(|traversal| traversal.position <= position);
+				let Traversal { position: sublist_position, node_index, .. } = traversal;
+				let sublist = self.skeleton_mut().get_or_add_sublist_at_mut(node_index);
+				sublist.insert_node_with_value(position - sublist_position, value);
+				self.skeleton_mut().add_nodes_at(node_index, 1);
+				self.skeleton_mut().update_largest_gap_at(node_index);
+				self.sublist_changed(node_index);
+				self.rebalance_sublist_if_deep(node_index);
+				self.check_invariants();
+			}
+		}
+
+		/// Like [`Self::insert_node_with_value`], but inserts the node in front of all nodes that are
+		/// already at `position`.
+		fn insert_node_in_front_with_value(&mut self, position: S, value: Self::Value)
+			where Self: SpacedList<S> {
+			if self.is_empty() || position > self.length() {
+				self.append_node_with_value(position - self.length(), value)
+			} else if position <= self.skeleton().get_node_position_at(1) {
+				self.prepend_node(position, value)
+			} else {
+				let mut traversal = self.traversal();
+				traversal.advance_while_shallow(|traversal| traversal.position < position);
+				let Traversal { position: sublist_position, node_index, .. } = traversal;
+				let sublist = self.skeleton_mut().get_or_add_sublist_at_mut(node_index);
+				sublist.insert_node_in_front_with_value(position - sublist_position, value);
+				self.skeleton_mut().add_nodes_at(node_index, 1);
+				self.skeleton_mut().update_largest_gap_at(node_index);
+				self.sublist_changed(node_index);
+				self.rebalance_sublist_if_deep(node_index);
+				self.check_invariants();
+			}
+		}
+
+		/// Inserts a node `offset` after the node at `node_index` of the list reached by following
+		/// `sublist_path` (see [`Self::descendant_mut`]), which must not be node zero. The new node
+		/// goes into the sublist of that node, or after it if it is the last node of its list, so it
+		/// must not come after the next node of that list, or, if it is the last one, after the node
+		/// following the list.
+		fn insert_after_node_at_path(&mut self, sublist_path: &[usize], node_index: usize, offset: S, value: Self::Value)
+			where Self: SpacedList<S> {
+			match sublist_path.split_first() {
+				None if node_index == self.slot_count() => self.append_node_with_value(offset, value),
+				None => {
+					let sublist = self.skeleton_mut().get_or_add_sublist_at_mut(node_index);
+					sublist.insert_node_with_value(offset, value);
+					self.skeleton_mut().add_nodes_at(node_index, 1);
+					self.skeleton_mut().update_largest_gap_at(node_index);
+					self.sublist_changed(node_index);
+					self.rebalance_sublist_if_deep(node_index);
+					self.check_invariants();
+				}
+				Some((&sublist_index, sublist_path)) => {
+					let sublist = self.skeleton_mut().sublist_at_mut(sublist_index).unwrap();
+					sublist.insert_after_node_at_path(sublist_path, node_index, offset, value);
+					self.skeleton_mut().add_nodes_at(sublist_index, 1);
+					self.skeleton_mut().update_largest_gap_at(sublist_index);
+					self.sublist_changed(sublist_index);
+					self.check_invariants();
+				}
+			}
+		}
+
+		/// Inserts a node at `position`, which must not be after the first node of this list.
+		fn prepend_node(&mut self, position: S, value: Self::Value) where Self: SpacedList<S> {
+			if self.slot_count() <= 1 {
+				if self.is_empty() {
+					self.append_node_with_value(position, value);
+				} else {
+					let distance = self.skeleton().get_node_position_at(1) - position;
+					self.skeleton_mut().deflate_at(0, distance);
+					let value = self.replace_value_at(1, value);
+					self.append_node_with_value(distance, value);
+				}
+				return;
+			}
+			let distance = self.skeleton().get_node_position_at(1) - position;
+			if self.skeleton().is_vacant(1) {
+				// the new node takes the vacant slot in front of the first node
+				self.skeleton_mut().occupy_at(1, distance);
+				self.occupy_value_at(1, value);
+				self.check_invariants();
+				return;
+			}
+			// the new node takes the place of the first node, which is moved to the front of the
+			// sublist of the new node, together with its own sublist
+			self.skeleton_mut().deflate_at(0, distance);
+			self.skeleton_mut().inflate_at(1, distance);
+			let value = self.replace_value_at(1, value);
+			// the sublist may be empty if it was added by Self::try_reserve_prepend
+			let sublist = self.skeleton_mut().get_or_add_sublist_at_mut(1);
+			if sublist.is_empty() {
+				sublist.append_node_with_value(distance, value);
+			} else {
+				sublist.skeleton_mut().inflate_at(0, distance);
+				sublist.prepend_node(distance, value);
+			}
+			self.skeleton_mut().add_nodes_at(1, 1);
+			self.skeleton_mut().update_largest_gap_at(1);
+			self.sublist_changed(1);
+			self.rebalance_sublist_if_deep(1);
+			self.check_invariants();
+		}
+
+		/// Like [`SpacedList::deflate_after`], but without checking whether nodes are moved to
+		/// before `position`.
+		fn deflate_after_unchecked(&mut self, position: S, amount: S) where Self: SpacedList<S> {
+			if self.is_empty() || position >= self.length() {
+				return;
+			}
+			let mut traversal = self.traversal();
+			traversal.advance_while_shallow(|traversal| traversal.position <= position);
+			let Traversal { position: node_position, node_index, .. } = traversal;
+			if let Some(sublist) = self.skeleton_mut().sublist_at_mut(node_index) {
+				sublist.deflate_after_unchecked(position - node_position, amount);
+			}
+			self.skeleton_mut().deflate_at(node_index, amount);
+			self.check_invariants();
+		}
+
+		/// Moves the node at `node_index` of the list reached by following `sublist_path` (see
+		/// [`Self::descendant_mut`]) and all nodes after it `amount` further back.
+		fn inflate_before_node_at_path(&mut self, sublist_path: &[usize], node_index: usize, amount: S)
+			where Self: SpacedList<S> {
+			match sublist_path.split_first() {
+				None => {
+					let previous_index = self.skeleton().previous_occupied(node_index);
+					self.skeleton_mut().inflate_at(previous_index, amount);
+				}
+				Some((&sublist_index, sublist_path)) => {
+					let sublist = self.skeleton_mut().sublist_at_mut(sublist_index).unwrap();
+					sublist.inflate_before_node_at_path(sublist_path, node_index, amount);
+					self.skeleton_mut().inflate_at(sublist_index, amount);
+				}
+			}
+			self.check_invariants();
+		}
+
+		/// Moves the node at `node_index` of the list reached by following `sublist_path` (see
+		/// [`Self::descendant_mut`]) and all nodes after it `amount` further forward.
+		fn deflate_before_node_at_path(&mut self, sublist_path: &[usize], node_index: usize, amount: S)
+			where Self: SpacedList<S> {
+			match sublist_path.split_first() {
+				None => {
+					let previous_index = self.skeleton().previous_occupied(node_index);
+					self.skeleton_mut().deflate_at(previous_index, amount);
+				}
+				Some((&sublist_index, sublist_path)) => {
+					let sublist = self.skeleton_mut().sublist_at_mut(sublist_index).unwrap();
+					sublist.deflate_before_node_at_path(sublist_path, node_index, amount);
+					self.skeleton_mut().deflate_at(sublist_index, amount);
+				}
+			}
+			self.check_invariants();
+		}
+
+		/// Like [`SpacedList::validate`], but reports violations with `sublist_path` prepended to
+		/// their path.
+		fn validate_at(&self, sublist_path: &mut Vec<usize>) -> Result<(), InvariantViolation>
+			where Self: SpacedList<S> {
+			let violation = |kind, sublist_path: &[usize]| InvariantViolation {
+				sublist_path: sublist_path.to_vec(),
+				kind,
+			};
+			let skeleton = self.skeleton();
+			let size = self.slot_count();
+			skeleton.check_invariants(size).map_err(|kind| violation(kind, sublist_path))?;
+			if self.value_count() != size {
+				return Err(violation(ViolationKind::InvalidSize, sublist_path));
+			}
+			for (node_index, link_node_count) in skeleton.link_node_counts().into_iter().enumerate() {
+				let sublist_node_count = skeleton.sublist_at(node_index).map_or(0, |sublist| sublist.deep_size());
+				let has_end_node = node_index < size && !skeleton.is_vacant(node_index + 1);
+				if link_node_count != sublist_node_count + usize::from(has_end_node) {
+					return Err(violation(ViolationKind::InvalidNodeCount { link_index: node_index }, sublist_path));
+				}
+				let sublist = match skeleton.sublist_at(node_index) {
+					Some(sublist) => sublist,
+					None => continue
+				};
+				let kind = if node_index == 0 || node_index >= size {
+					Some(ViolationKind::MisplacedSublist { node_index })
+				} else if sublist.is_empty() {
+					Some(ViolationKind::EmptySublist { node_index })
+				} else if sublist.skeleton().get_node_position_at(1) < zero()
+					|| sublist.length() > skeleton.get_distance_at(node_index) {
+					Some(ViolationKind::SublistOutOfBounds { node_index })
+				} else {
+					None
+				};
+				if let Some(kind) = kind {
+					return Err(violation(kind, sublist_path));
+				}
+				sublist_path.push(node_index);
+				sublist.validate_at(sublist_path)?;
+				sublist_path.pop();
+			}
+			// checked last, as this also breaks when the distances or sublists it depends on are broken
+			let gaps = skeleton.link_largest_gaps().into_iter().zip(skeleton.distances());
+			for (link_index, (largest_gap, distance)) in gaps.enumerate() {
+				if largest_gap != skeleton.largest_gap_in_link(link_index, distance) {
+					return Err(violation(ViolationKind::InvalidLargestGap { link_index }, sublist_path));
+				}
+			}
+			Ok(())
+		}
+
+		/// With the `paranoid` feature enabled, validates this list (see [`SpacedList::validate`])
+		/// and panics with a diagram of the skeleton the first violation was found in, highlighting
+		/// the broken link or node. Does nothing otherwise.
+		///
+		/// Every mutating method calls this when it is done, on every level of sublists it changed.
+		fn check_invariants(&self) where Self: SpacedList<S> {
+			#[cfg(feature = "paranoid")]
+			if let Err(violation) = self.validate() {
+				let mut list = self;
+				for &node_index in &violation.sublist_path {
+					list = list.skeleton().sublist_at(node_index).unwrap();
+				}
+				let (links, nodes) = match violation.kind {
+					ViolationKind::NegativeDistance { link_index }
+					| ViolationKind::DistanceAfterLastNode { link_index }
+					| ViolationKind::InvalidNodeCount { link_index }
+					| ViolationKind::InvalidLargestGap { link_index } => (vec![link_index], vec![]),
+					ViolationKind::MisplacedSublist { node_index }
+					| ViolationKind::EmptySublist { node_index }
+					| ViolationKind::InvalidVacancy { node_index } => (vec![], vec![node_index]),
+					ViolationKind::SublistOutOfBounds { node_index } => (vec![node_index], vec![node_index]),
+					ViolationKind::InvalidCapacity | ViolationKind::InvalidSize => (vec![], vec![])
+				};
+				panic!("{}\n{:?}", violation, list.skeleton().highlighted_format(links, nodes));
+			}
+		}
+
+		/// Rebalances the sublist of the node at `node_index` (see [`SpacedList::rebalance`]) if more
+		/// than half of its nodes are in sublists of its own.
+		///
+		/// Called after every insertion into a sublist, this keeps every sublist's sublists at most
+		/// half as large as it, and thereby the nesting depth logarithmic, while a sublist of `n`
+		/// nodes is only rebalanced after at least about `n` insertions into it.
+		///
+		/// As rebalancing is not necessary for the list to work, it is skipped if the memory it needs
+		/// can't be allocated.
+		fn rebalance_sublist_if_deep(&mut self, node_index: usize) where Self: SpacedList<S> {
+			if let Some(sublist) = self.skeleton_mut().sublist_at_mut(node_index) {
+				let (deep_size, size) = (sublist.deep_size(), sublist.size());
+				if deep_size > 2 * size {
+					let mut nodes = vec![];
+					if nodes.try_reserve_exact(deep_size).is_ok() && sublist.try_reserve(deep_size - size).is_ok() {
+						sublist.rebuild_flat(nodes);
+					}
+				}
+			}
+		}
+
+		/// Moves all nodes into this list's skeleton, see [`SpacedList::rebalance`], using `nodes`,
+		/// which must be empty, as temporary storage for them.
+		fn rebuild_flat(&mut self, mut nodes: Vec<(S, Self::Value)>) where Self: SpacedList<S> {
+			self.drain_reversed(zero(), &mut nodes);
+			let mut previous = zero();
+			for (position, value) in nodes.into_iter().rev() {
+				self.append_node_with_value(position - previous, value);
+				previous = position;
+			}
+		}
+
+		/// Reserves the memory needed for inserting a node at `position` (see
+		/// [`Self::insert_node_with_value`]) in all lists the node is inserted into, so that
+		/// inserting it right afterwards doesn't allocate.
+		///
+		/// This may add empty sublists, which the insertion then adds the node to.
+		fn try_reserve_insertion(&mut self, position: S) -> Result<(), SpacedListError> where Self: SpacedList<S> {
+			if self.is_empty() || position >= self.length() {
+				self.try_reserve(1)
+			} else if position < self.skeleton().get_node_position_at(1) {
+				self.try_reserve_prepend()
+			} else {
+				let mut traversal = self.traversal();
+				traversal.advance_while_shallow(|traversal| traversal.position <= position);
+				let Traversal { position: sublist_position, node_index, .. } = traversal;
+				self.try_reserve_in_sublist(node_index, |sublist| sublist.try_reserve_insertion(position - sublist_position))
+			}
+		}
+
+		/// Like [`Self::try_reserve_insertion`], for [`Self::prepend_node`].
+		fn try_reserve_prepend(&mut self) -> Result<(), SpacedListError> where Self: SpacedList<S> {
+			if self.slot_count() <= 1 {
+				self.try_reserve(1)
+			} else if self.skeleton().is_vacant(1) {
+				Ok(())
+			} else {
+				self.try_reserve_in_sublist(1, |sublist| sublist.try_reserve_prepend())
+			}
+		}
+
+		/// Calls `reserve` with the sublist of the node at `node_index`, adding an empty one if it
+		/// has none, which is removed again if `reserve` fails.
+		fn try_reserve_in_sublist<F>(&mut self, node_index: usize, reserve: F) -> Result<(), SpacedListError>
+			where Self: SpacedList<S>, F: FnOnce(&mut Self) -> Result<(), SpacedListError> {
+			let sublist = self.skeleton_mut().get_or_add_sublist_at_mut(node_index);
+			let result = reserve(sublist);
+			if result.is_err() && sublist.is_empty() {
+				*self.skeleton_mut().get_sublist_at_mut(node_index) = None;
+			}
+			result
+		}
+
+		/// Removes all nodes of this list, including the ones in sublists, and pushes them to
+		/// `nodes` in reverse order, together with their positions plus `offset`.
+		fn drain_reversed(&mut self, offset: S, nodes: &mut Vec<(S, Self::Value)>) where Self: SpacedList<S> {
+			while !self.is_empty() {
+				let position = offset + self.length();
+				let (_, value, sublist) = self.pop_skeleton_node();
+				// the nodes of the sublist come after the node it belongs to
+				if let Some(mut sublist) = sublist {
+					sublist.drain_reversed(position, nodes);
+				}
+				nodes.push((position, value));
+			}
+		}
+
+		/// Makes `sublist` the sublist of the node at `node_index`, which must be followed by another
+		/// node and must not have a sublist yet.
+		fn set_sublist_at(&mut self, node_index: usize, sublist: Self) where Self: SpacedList<S> {
+			let node_count = sublist.deep_size();
+			*self.skeleton_mut().get_sublist_at_mut(node_index) = Some(sublist);
+			self.skeleton_mut().add_nodes_at(node_index, node_count);
+			self.skeleton_mut().update_largest_gap_at(node_index);
+			self.sublist_changed(node_index);
+			self.check_invariants();
+		}
+
+		/// Removes the last node of this list's skeleton and returns the distance from the node
+		/// before it, its value and its sublist.
+		///
+		/// Unlike [`Self::remove_skeleton_node_at`], this leaves the sublist of the new last node
+		/// where it is, see [`Self::move_last_sublist_node_out`].
+		fn pop_skeleton_node(&mut self) -> (S, Self::Value, Option<Self>) where Self: SpacedList<S> {
+			let node_index = self.slot_count();
+			let sublist = if node_index < self.capacity() {
+				self.skeleton_mut().get_sublist_at_mut(node_index).take()
+			} else {
+				None
+			};
+			if let Some(sublist) = &sublist {
+				self.skeleton_mut().remove_nodes_at(node_index, sublist.deep_size());
+				self.skeleton_mut().update_largest_gap_at(node_index);
+				self.sublist_changed(node_index);
+			}
+			let (distance, value) = self.remove_last_slot();
+			(distance, value, sublist)
+		}
+
+		/// Removes the node in the last slot, which must not have a sublist, together with the
+		/// vacant slots before it, so that the last slot is never vacant. Returns the distance of
+		/// the node from the node before it and its value.
+		fn remove_last_slot(&mut self) -> (S, Self::Value) where Self: SpacedList<S> {
+			let node_index = self.slot_count();
+			let mut distance = self.skeleton().get_distance_at(node_index - 1);
+			self.skeleton_mut().deflate_at(node_index - 1, distance);
+			self.skeleton_mut().remove_nodes_at(node_index - 1, 1);
+			*self.slot_count_mut() -= 1;
+			let value = self.remove_value_at(node_index);
+			while self.skeleton().is_vacant(self.slot_count()) {
+				let node_index = self.slot_count();
+				// the distance of the node is the one from the last node before the vacant slots
+				let vacant_distance = self.skeleton().get_distance_at(node_index - 1);
+				self.skeleton_mut().deflate_at(node_index - 1, vacant_distance);
+				self.skeleton_mut().clear_vacancy_at(node_index);
+				*self.slot_count_mut() -= 1;
+				self.remove_vacant_value_at(node_index);
+				distance += vacant_distance;
+			}
+			(distance, value)
+		}
+
+		/// The last node must not have a sublist, so if it has one, the last node of that sublist is
+		/// moved out of it to become the new last node.
+		fn move_last_sublist_node_out(&mut self) where Self: SpacedList<S> {
+			let last_index = self.slot_count();
+			if let Some(sublist) = self.skeleton_mut().sublist_at_mut(last_index) {
+				let distance = sublist.length();
+				let size = sublist.slot_count();
+				let value = sublist.remove_skeleton_node_at(size);
+				if sublist.is_empty() {
+					*self.skeleton_mut().get_sublist_at_mut(last_index) = None;
+				}
+				self.skeleton_mut().remove_nodes_at(last_index, 1);
+				self.skeleton_mut().update_largest_gap_at(last_index);
+				self.sublist_changed(last_index);
+				self.append_node_with_value(distance, value);
+			}
+		}
+
+		/// Removes the node at `position` and returns its value, keeping all other nodes at their
+		/// positions.
+		///
+		/// If there are multiple nodes at `position`, the last one is removed.
+		fn take_node_at(&mut self, position: S) -> Option<Self::Value> where Self: SpacedList<S> {
+			let traversal = self.node_at(position)?;
+			let sublist_path = traversal.sublist_path();
+			let node_index = traversal.node_index;
+			Some(self.take_node_at_path(&sublist_path, node_index))
+		}
+
+		/// Removes the node at `node_index` of the list reached by following `sublist_path` (see
+		/// [`Self::descendant_mut`]) and returns its value, keeping all other nodes at their
+		/// positions.
+		fn take_node_at_path(&mut self, sublist_path: &[usize], node_index: usize) -> Self::Value
+			where Self: SpacedList<S> {
+			match sublist_path.split_first() {
+				None => self.remove_skeleton_node_at(node_index),
+				Some((&sublist_index, sublist_path)) => {
+					let sublist = self.skeleton_mut().sublist_at_mut(sublist_index).unwrap();
+					let value = sublist.take_node_at_path(sublist_path, node_index);
+					if sublist.is_empty() {
+						*self.skeleton_mut().get_sublist_at_mut(sublist_index) = None;
+					}
+					self.skeleton_mut().remove_nodes_at(sublist_index, 1);
+					self.skeleton_mut().update_largest_gap_at(sublist_index);
+					self.sublist_changed(sublist_index);
+					self.check_invariants();
+					value
+				}
+			}
+		}
+
+		/// Removes the node at `node_index` of this list's skeleton and returns its value, keeping
+		/// all other nodes at their positions.
+		///
+		/// If the node has a sublist, the first node of the sublist takes its place, if it is the
+		/// last node, its slot is removed, and otherwise its slot is left vacant (see
+		/// [`SpacedListSkeleton::vacate_at`]). Either way, this takes logarithmic time.
+		fn remove_skeleton_node_at(&mut self, node_index: usize) -> Self::Value where Self: SpacedList<S> {
+			let slot_count = self.slot_count();
+			let previous_index = self.skeleton().previous_occupied(node_index);
+			let value = if let Some(sublist) = self.skeleton_mut().sublist_at_mut(node_index) {
+				let distance = sublist.skeleton().get_node_position_at(1);
+				let first_index = sublist.skeleton().next_occupied(0);
+				let value = sublist.remove_skeleton_node_at(first_index);
+				if sublist.is_empty() {
+					*self.skeleton_mut().get_sublist_at_mut(node_index) = None;
+				} else {
+					sublist.skeleton_mut().deflate_at(0, distance);
+				}
+				self.skeleton_mut().inflate_at(previous_index, distance);
+				if node_index < slot_count {
+					self.skeleton_mut().deflate_at(node_index, distance);
+				}
+				self.skeleton_mut().remove_nodes_at(node_index, 1);
+				self.skeleton_mut().update_largest_gap_at(node_index);
+				self.sublist_changed(node_index);
+				self.replace_value_at(node_index, value)
+			} else if node_index == slot_count {
+				let (_, removed_value) = self.remove_last_slot();
+				self.move_last_sublist_node_out();
+				removed_value
+			} else {
+				self.skeleton_mut().vacate_at(node_index);
+				self.vacate_value_at(node_index)
+			};
+			self.shrink_if_sparse();
+			self.check_invariants();
+			value
+		}
 	}
 }
 
@@ -38,91 +645,492 @@ pub trait SpacedList<S: Spacing>: CrateSpacedList<S> {
 
 	fn capacity(&self) -> usize;
 
+	/// The number of nodes in this list and all of its sublists, as opposed to [`Self::size`],
+	/// which only counts the nodes of this list's skeleton.
+	fn deep_size(&self) -> usize {
+		self.skeleton().node_count()
+	}
+
 	fn is_empty(&self) -> bool {
 		self.size() == 0
 	}
 
+	/// Whether appending a node to this list's skeleton requires growing it, which is the case
+	/// when all of its slots are in use.
 	fn is_full(&self) -> bool {
-		self.size() == self.capacity()
+		self.slot_count() == self.capacity()
 	}
 
-	fn append_node(&mut self, distance: S) {
-		if self.is_full() {
-			self.grow()
-		}
+	/// Checks the structural invariants of this list and all of its sublists, returning the first
+	/// violation found.
+	///
+	/// This checks the capacity and size of every list, that the distances between nodes implied
+	/// by the link lengths are non-negative and zero after the last node, that vacant slots are at
+	/// the position of the next node, and that every sublist is non-empty and lies between the node
+	/// it belongs to and the next one.
+	fn validate(&self) -> Result<(), InvariantViolation> {
+		self.validate_at(&mut vec![])
+	}
+
+	/// Iterates over the positions of all nodes, including the ones in sublists, in order.
+	fn iter(&self) -> Iter<'_, S, Self> {
+		Iter::new(self)
+	}
+
+	/// Iterates over the positions of all nodes within `bounds`, including the ones in sublists, in
+	/// order.
+	///
+	/// # Panics
+	///
+	/// Panics if the start of `bounds` is greater than its end, or if they are equal and both
+	/// excluded.
+	fn range<R: RangeBounds<S>>(&self, bounds: R) -> Range<'_, S, Self> {
+		Range::new(self, bounds)
+	}
+
+	/// A cursor resting on node zero, which can be moved through the list and edit it at the node
+	/// it rests on.
+	fn cursor_mut(&mut self) -> CursorMut<'_, S, Self> {
+		CursorMut::new(self)
+	}
 
-		let size = self.size();
-		self.skeleton_mut().inflate_at(size, distance);
-		*self.size_mut() += 1;
+	/// For lists that store values, the new node gets the default value.
+	fn append_node(&mut self, distance: S) where Self::Value: Default {
+		self.append_node_with_value(distance, Default::default())
 	}
 
-	fn node_before(&self, position: S) -> Option<Traversal<S, Self>> {
+	fn node_before(&self, position: S) -> Option<Traversal<'_, S, Self>> {
 		let traversal = self.traversal().advance_while(|traversal| traversal.position < position);
-		if traversal.position < position {
+		if traversal.node_index > 0 {
 			Some(traversal)
 		} else {
 			None
 		}
 	}
 
-	fn node_at_or_before(&self, position: S) -> Option<Traversal<S, Self>> {
+	fn node_at_or_before(&self, position: S) -> Option<Traversal<'_, S, Self>> {
 		let traversal = self.traversal().advance_while(|traversal| traversal.position <= position);
-		if traversal.position <= position {
+		if traversal.node_index > 0 {
 			Some(traversal)
 		} else {
 			None
 		}
 	}
 
-	fn node_at(&self, position: S) -> Option<Traversal<S, Self>> {
+	/// If there are multiple nodes at `position`, this returns the last one.
+	fn node_at(&self, position: S) -> Option<Traversal<'_, S, Self>> {
 		let traversal = self.traversal().advance_while(|traversal| traversal.position <= position);
-		if traversal.position == position {
+		if traversal.node_index > 0 && traversal.position == position {
 			Some(traversal)
 		} else {
 			None
 		}
 	}
 
-	fn node_at_or_after(&self, position: S) -> Option<Traversal<S, Self>> {
-		if position > self.length() {
+	/// If there are multiple nodes at the found position, this returns the first one.
+	fn node_at_or_after(&self, position: S) -> Option<Traversal<'_, S, Self>> {
+		if self.is_empty() || position > self.length() {
 			return None;
 		}
-		let traversal = self.traversal().advance_while(|traversal| traversal.position <= position);
-		if traversal.position == position {
-			Some(traversal)
-		} else {
-			Some(traversal.advance())
-		}
+		let traversal = self.traversal().advance_while(|traversal| traversal.position < position);
+		Some(traversal.advance())
 	}
 
-	fn node_after(&self, position: S) -> Option<Traversal<S, Self>> {
-		if position >= self.length() {
+	/// If there are multiple nodes at the found position, this returns the first one.
+	fn node_after(&self, position: S) -> Option<Traversal<'_, S, Self>> {
+		if self.is_empty() || position >= self.length() {
 			return None;
 		}
 		let traversal = self.traversal().advance_while(|traversal| traversal.position <= position);
 		Some(traversal.advance())
 	}
 
-	fn insert_node(&mut self, position: S) {
-		if position >= self.length() || self.capacity() == 0 {
-			self.append_node(position - self.length())
+	/// The number of nodes before `position`, including the ones in sublists.
+	fn rank(&self, position: S) -> usize {
+		self.node_before(position).map_or(0, |traversal| traversal.node_count_up_to())
+	}
+
+	/// The position of the node with `index` nodes before it, including the ones in sublists, or
+	/// `None` if there are not that many nodes.
+	fn select(&self, index: usize) -> Option<S> {
+		self.nth_node(index).map(|traversal| traversal.position)
+	}
+
+	/// The node with `index` nodes before it, including the ones in sublists, or `None` if there
+	/// are not that many nodes.
+	fn nth_node(&self, index: usize) -> Option<Traversal<'_, S, Self>> {
+		if index >= self.deep_size() {
+			return None;
+		}
+		let mut sublist_path = vec![];
+		let mut list = self;
+		let mut count = index + 1;
+		loop {
+			// the last slot before the node, which is either the next node after that slot or in its
+			// sublist
+			let (node_index, node_count) =
+				list.skeleton().last_node_with_count_up_to_at_most(list.slot_count(), count - 1);
+			let sublist_node_count = list.skeleton().sublist_at(node_index).map_or(0, |sublist| sublist.deep_size());
+			if node_count + sublist_node_count < count {
+				return Some(Traversal::at_path(self, &sublist_path, node_index + 1));
+			}
+			sublist_path.push(node_index);
+			list = list.skeleton().sublist_at(node_index).unwrap();
+			count -= node_count;
+		}
+	}
+
+	/// The number of nodes before the node at `position`, including the ones in sublists.
+	///
+	/// If there are multiple nodes at `position`, this returns the index of the last one.
+	fn index_of(&self, position: S) -> Option<usize> {
+		self.node_at(position).map(|traversal| traversal.node_count_up_to() - 1)
+	}
+
+	/// The largest distance between two neighbouring nodes, including the ones in sublists, or
+	/// `None` if there are less than two nodes.
+	fn largest_gap(&self) -> Option<S> {
+		(self.deep_size() >= 2).then(|| self.skeleton().largest_gap())
+	}
+
+	/// The position of the first node, including the ones in sublists, whose distance to the node
+	/// after it is at least `size`, or `None` if there is no such node.
+	///
+	/// This takes time logarithmic in the size of every list it descends into, and descends into at
+	/// most one sublist per level.
+	fn first_gap_at_least(&self, size: S) -> Option<S> {
+		if self.deep_size() < 2 {
+			return None;
+		}
+		if size <= zero() {
+			return Some(self.skeleton().get_node_position_at(1));
+		}
+		let skeleton = self.skeleton();
+		let link_index = skeleton.first_link_with_gap_at_least(size)?;
+		let position = skeleton.get_node_position_at(link_index);
+		let sublist = match skeleton.sublist_at(link_index) {
+			Some(sublist) => sublist,
+			None => return Some(position)
+		};
+		// the gap is either before the sublist, within it or after it
+		if sublist.skeleton().get_node_position_at(1) >= size {
+			Some(position)
+		} else if let Some(sublist_position) = sublist.first_gap_at_least(size) {
+			Some(position + sublist_position)
 		} else {
-			let mut traversal = self.traversal();
-			traversal.advance_while_shallow(|traversal| traversal.position <= position);
-			let Traversal {
-				degree: _,
-				position: sublist_position,
-				node_index,
-				link_index: _,
-				list,
-				super_traversal: _
-			} = traversal;
-			let sublist = self.skeleton_mut().get_sublist_at_mut(node_index).get_or_insert_default();
-			sublist.insert_node(position - sublist_position)
+			Some(position + sublist.length())
+		}
+	}
+
+	/// Inserts a node at `position`, after all nodes that are already at that position.
+	///
+	/// For lists that store values, the new node gets the default value.
+	fn insert_node(&mut self, position: S) where Self::Value: Default {
+		self.insert_node_with_value(position, Default::default())
+	}
+
+	/// Moves all nodes after `position` (but not the ones at `position`) `amount` further back,
+	/// inserting space of that size right after `position`.
+	fn inflate_after(&mut self, position: S, amount: S) {
+		if self.is_empty() || position >= self.length() {
+			return;
+		}
+		let mut traversal = self.traversal();
+		traversal.advance_while_shallow(|traversal| traversal.position <= position);
+		let Traversal { position: node_position, node_index, .. } = traversal;
+		if let Some(sublist) = self.skeleton_mut().sublist_at_mut(node_index) {
+			sublist.inflate_after(position - node_position, amount);
+		}
+		self.skeleton_mut().inflate_at(node_index, amount);
+		self.check_invariants();
+	}
+
+	/// Grows the capacity so that at least `additional` more nodes can be appended to this list's
+	/// skeleton without growing it again.
+	fn reserve(&mut self, additional: usize) {
+		while self.capacity() < self.slot_count() + additional {
+			self.grow();
+		}
+	}
+
+	/// Like [`Self::reserve`], but returns an error instead of aborting if the memory can't be
+	/// allocated. For lists that store values, this also reserves the memory for `additional`
+	/// more values.
+	fn try_reserve(&mut self, additional: usize) -> Result<(), SpacedListError> {
+		// all memory is reserved before growing, as growing step by step could succeed in
+		// allocating and filling a lot of memory before failing
+		let capacity = self.slot_count().checked_add(additional)
+			.and_then(usize::checked_next_power_of_two)
+			.ok_or(SpacedListError::AllocationFailed)?;
+		self.try_reserve_capacity(capacity).map_err(|_| SpacedListError::AllocationFailed)?;
+		self.try_reserve_values(additional).map_err(|_| SpacedListError::AllocationFailed)?;
+		self.reserve(additional);
+		Ok(())
+	}
+
+	/// Shrinks the capacity of this list and all of its sublists as much as possible, which is to
+	/// the smallest power of two its skeleton's nodes fit into once the slots of removed nodes are
+	/// reused.
+	///
+	/// Removing nodes also shrinks the capacity once no more than a quarter of it is used.
+	fn shrink_to_fit(&mut self) {
+		self.remove_vacancies();
+		while self.capacity() > 0 && self.size() <= self.capacity() / 2 {
+			self.shrink();
+		}
+		for sublist in self.skeleton_mut().sublists_mut() {
+			sublist.shrink_to_fit();
 		}
+		self.check_invariants();
+	}
+
+	/// The heap memory used by this list and all of its sublists, with a breakdown of how much of it
+	/// is unused capacity and how deeply the nodes are nested, which can help to decide whether to
+	/// call [`Self::shrink_to_fit`] or [`Self::rebalance`].
+	///
+	/// This takes time linear in the number of sublists.
+	fn memory_usage(&self) -> MemoryUsage {
+		let mut usage = MemoryUsage::default();
+		self.skeleton().add_memory_usage(self.slot_count(), &mut usage);
+		self.add_value_memory_usage(&mut usage);
+		if !self.is_empty() {
+			usage.depth_histogram.push(self.size());
+		}
+		for sublist in self.skeleton().sublists() {
+			usage.add_sublist(sublist.memory_usage());
+		}
+		usage
+	}
+
+	/// Moves all nodes out of sublists into this list's skeleton, keeping their positions and
+	/// values, so that reaching any of them doesn't require descending into sublists, and reuses
+	/// the slots of removed nodes.
+	///
+	/// This takes time linear in the number of nodes times the logarithm of the capacity. Sublists
+	/// are also rebalanced automatically when more than half of their nodes are nested in sublists
+	/// of their own.
+	fn rebalance(&mut self) {
+		if self.deep_size() == self.size() {
+			self.remove_vacancies();
+			return;
+		}
+		self.rebuild_flat(Vec::with_capacity(self.deep_size()));
+	}
+
+	/// Moves all nodes at or after `position` into a new list and returns it, like
+	/// [`Vec::split_off`]. In the new list, the nodes are positioned relative to `position`.
+	///
+	/// The sublists of the moved nodes are moved along with them, so this takes time logarithmic
+	/// in the size of this list for every node of its skeleton that is moved, and descends into one
+	/// sublist per level.
+	fn split_off(&mut self, position: S) -> Self {
+		if self.is_empty() || position > self.length() {
+			return Self::default();
+		}
+		let mut traversal = self.traversal();
+		traversal.advance_while_shallow(|traversal| traversal.position < position);
+		let Traversal { position: node_position, node_index, .. } = traversal;
+		let first_position = self.skeleton().get_node_position_at(node_index + 1) - position;
+		// the nodes of the sublist of the last node before position come first
+		let mut tail = match self.skeleton_mut().sublist_at_mut(node_index) {
+			Some(sublist) => {
+				let tail = sublist.split_off(position - node_position);
+				if sublist.is_empty() {
+					*self.skeleton_mut().get_sublist_at_mut(node_index) = None;
+				}
+				self.skeleton_mut().remove_nodes_at(node_index, tail.deep_size());
+				self.skeleton_mut().update_largest_gap_at(node_index);
+				self.sublist_changed(node_index);
+				tail
+			}
+			None => Self::default()
+		};
+		let mut nodes = vec![];
+		while self.slot_count() > node_index {
+			nodes.push(self.pop_skeleton_node());
+		}
+		self.move_last_sublist_node_out();
+		self.shrink_if_sparse();
+		self.check_invariants();
+
+		let mut sublists = vec![];
+		for (index, (distance, value, sublist)) in nodes.into_iter().rev().enumerate() {
+			let distance = if index == 0 { first_position - tail.length() } else { distance };
+			tail.append_node_with_value(distance, value);
+			if let Some(sublist) = sublist {
+				sublists.push((tail.slot_count(), sublist));
+			}
+		}
+		// sublists can only be added once the nodes after them exist
+		for (node_index, sublist) in sublists {
+			tail.set_sublist_at(node_index, sublist);
+		}
+		tail
+	}
+
+	/// Moves all nodes of `other` to the end of this list, such that node zero of `other` ends up
+	/// `gap` after the last node of this list. Inverse of [`Self::split_off`].
+	///
+	/// `other` is reused as the sublist of the last node, so this takes time logarithmic in the size
+	/// of both lists, no matter how many nodes `other` has.
+	///
+	/// # Panics
+	///
+	/// Panics if this list isn't empty and the first node of `other` would end up before its last
+	/// node.
+	fn append_list(&mut self, gap: S, mut other: Self) {
+		if other.is_empty() {
+			return;
+		}
+		assert!(self.is_empty() || gap + other.skeleton().get_node_position_at(1) >= zero(),
+		        "Cannot append a list whose first node would be before the last node");
+		other.skeleton_mut().inflate_at(0, gap);
+		if self.is_empty() {
+			*self = other;
+			self.check_invariants();
+			return;
+		}
+		if self.is_full() {
+			self.grow();
+		}
+		let last_index = self.slot_count();
+		let node_count = other.deep_size();
+		*self.skeleton_mut().get_sublist_at_mut(last_index) = Some(other);
+		self.skeleton_mut().add_nodes_at(last_index, node_count);
+		self.skeleton_mut().update_largest_gap_at(last_index);
+		self.sublist_changed(last_index);
+		self.move_last_sublist_node_out();
+	}
+
+	/// Like [`Self::append_node`], but returns an error instead of aborting if the memory needed
+	/// can't be allocated.
+	fn try_append_node(&mut self, distance: S) -> Result<(), SpacedListError> where Self::Value: Default {
+		self.try_reserve(1)?;
+		self.append_node(distance);
+		Ok(())
+	}
+
+	/// Like [`Self::insert_node`], but returns an error instead of overflowing if the distance
+	/// between `position` and another position in the list is not representable, or instead of
+	/// aborting if the memory needed can't be allocated.
+	fn try_insert_node(&mut self, position: S) -> Result<(), SpacedListError>
+		where Self::Value: Default, S: CheckedSub {
+		let (low, high) = self.position_bounds();
+		high.max(position).checked_sub(&low.min(position)).ok_or(SpacedListError::PositionOverflow)?;
+		self.try_reserve_insertion(position)?;
+		self.insert_node(position);
+		Ok(())
+	}
+
+	/// Like [`Self::inflate_after`], but returns an error instead of breaking the order of the
+	/// nodes if `amount` is negative, or overflowing if the list would become too long.
+	fn try_inflate_after(&mut self, position: S, amount: S) -> Result<(), SpacedListError>
+		where S: CheckedAdd + CheckedSub {
+		if amount < zero() {
+			return Err(SpacedListError::NegativeAmount);
+		}
+		if self.is_empty() || position >= self.length() {
+			return Ok(());
+		}
+		let (low, high) = self.position_bounds();
+		high.checked_add(&amount)
+		    .and_then(|high| high.checked_sub(&low))
+		    .ok_or(SpacedListError::PositionOverflow)?;
+		self.inflate_after(position, amount);
+		Ok(())
+	}
+
+	/// Moves all nodes after `position` (but not the ones at `position`) `amount` further forward,
+	/// removing space of that size right after `position`.
+	///
+	/// # Panics
+	///
+	/// Panics if this would move a node to before `position`, which is the case if there are nodes
+	/// after `position` that are closer to it than `amount`.
+	fn deflate_after(&mut self, position: S, amount: S) where S: CheckedAdd {
+		let next_position = match self.node_after(position) {
+			Some(traversal) => traversal.position,
+			None => return
+		};
+		// the distance to the next node may not fit into S, but then it is larger than amount
+		assert!(
+			position.checked_add(&amount).is_some_and(|end| end <= next_position),
+			"Cannot deflate past the node after position"
+		);
+		self.deflate_after_unchecked(position, amount);
+	}
+
+	/// Like [`Self::deflate_after`], but returns an error instead of panicking if this would move a
+	/// node to before `position`, or breaking the order of the nodes if `amount` is negative.
+	fn try_deflate_after(&mut self, position: S, amount: S) -> Result<(), SpacedListError>
+		where S: CheckedAdd {
+		if amount < zero() {
+			return Err(SpacedListError::NegativeAmount);
+		}
+		let next_position = match self.node_after(position) {
+			Some(traversal) => traversal.position,
+			None => return Ok(())
+		};
+		if position.checked_add(&amount).is_none_or(|end| end > next_position) {
+			return Err(SpacedListError::DeflationPastNode);
+		}
+		self.deflate_after_unchecked(position, amount);
+		Ok(())
+	}
+
+	/// Removes the node at `position`, keeping all other nodes at their positions.
+	///
+	/// If there are multiple nodes at `position`, the last one is removed. Returns whether there
+	/// was a node to remove.
+	///
+	/// This takes time logarithmic in the size of every list it descends into. The slot of a node
+	/// that has no sublist and isn't the last node of its list is left vacant rather than moving
+	/// the nodes after it, and is reused once the list shrinks, see [`Self::shrink_to_fit`].
+	fn remove_node_at(&mut self, position: S) -> bool {
+		self.take_node_at(position).is_some()
+	}
+
+	/// Removes the last node before `position`, keeping all other nodes at their positions, and
+	/// returns the position of the removed node.
+	fn remove_node_before(&mut self, position: S) -> Option<S> {
+		let traversal = self.node_before(position)?;
+		let (sublist_path, node_index, position) =
+			(traversal.sublist_path(), traversal.node_index, traversal.position);
+		self.take_node_at_path(&sublist_path, node_index);
+		Some(position)
+	}
+
+	/// Removes the first node after `position`, keeping all other nodes at their positions, and
+	/// returns the position of the removed node.
+	fn remove_node_after(&mut self, position: S) -> Option<S> {
+		let traversal = self.node_after(position)?;
+		let (sublist_path, node_index, position) =
+			(traversal.sublist_path(), traversal.node_index, traversal.position);
+		self.take_node_at_path(&sublist_path, node_index);
+		Some(position)
 	}
 }
 
 pub(crate) mod hollow;
 
+pub(crate) mod filled;
+
+pub(crate) mod aggregated;
+
+pub(crate) mod range;
+
+pub(crate) mod filled_range;
+
+pub(crate) mod nested_range;
+
+pub(crate) mod iter;
+
+pub(crate) mod cursor;
+
+pub(crate) mod error;
+
+pub(crate) mod memory;
+
 mod skeleton;
+
+mod tests;
@@ -0,0 +1,295 @@
+use std::ops::Range;
+
+use crate::spaced_lists::{CrateSpacedList, SpacedList, Spacing};
+use crate::spaced_lists::aggregated::{Aggregate, AggregatedSpacedList};
+
+/// The side a range bound sticks to when space is inserted exactly at its position.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StickySide {
+	/// The bound stays where it is, so the inserted space ends up after it.
+	Left,
+	/// The bound moves along with the nodes after it, so the inserted space ends up before it.
+	Right,
+}
+
+/// The value of a node of a [`NestedRangeSpacedList`], which holds the index of the value of the
+/// range it belongs to.
+#[derive(Clone, Copy)]
+pub(crate) enum NestedRangeNode {
+	/// The start of a range whose end is `end_offset` range bounds after it.
+	Start { index: usize, end_offset: usize },
+	End { index: usize },
+}
+
+/// The number of range bounds in a sequence of them, and the offset of the last end of the ranges
+/// starting in that sequence from its first bound, if any start in it.
+#[derive(Clone, Copy)]
+pub(crate) struct RangeEnds {
+	count: usize,
+	last_end: Option<usize>,
+}
+
+impl Aggregate<NestedRangeNode> for RangeEnds {
+	fn empty() -> Self {
+		Self { count: 0, last_end: None }
+	}
+
+	fn of(node: &NestedRangeNode) -> Self {
+		match *node {
+			NestedRangeNode::Start { end_offset, .. } => Self { count: 1, last_end: Some(end_offset) },
+			NestedRangeNode::End { .. } => Self { count: 1, last_end: None }
+		}
+	}
+
+	fn combine(&self, other: &Self) -> Self {
+		Self {
+			count: self.count + other.count,
+			last_end: self.last_end.max(other.last_end.map(|last_end| self.count + last_end)),
+		}
+	}
+}
+
+/// A spaced list of non-empty ranges that may overlap and nest arbitrarily, storing a value for
+/// every range.
+///
+/// Every range is represented by a node at its start and a node at its end. At the same position,
+/// the nodes of bounds sticking to the left always come before the ones sticking to the right.
+pub struct NestedRangeSpacedList<S: Spacing, T> {
+	list: AggregatedSpacedList<S, NestedRangeNode, RangeEnds>,
+	values: Vec<Option<T>>,
+	free_indices: Vec<usize>,
+	size: usize,
+	start_side: StickySide,
+	end_side: StickySide,
+}
+
+impl<S: Spacing, T> Default for NestedRangeSpacedList<S, T> {
+	fn default() -> Self {
+		Self::with_sticky_sides(StickySide::Left, StickySide::Left)
+	}
+}
+
+impl<S: Spacing, T> NestedRangeSpacedList<S, T> {
+	/// Creates a list whose range starts and ends both stick to the left, so inserting space at the
+	/// start of a range makes it grow, and inserting space at its end doesn't.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Creates a list whose range starts stick to `start_side` and whose range ends stick to
+	/// `end_side` when space is inserted exactly at their position.
+	pub fn with_sticky_sides(start_side: StickySide, end_side: StickySide) -> Self {
+		Self {
+			list: Default::default(),
+			values: vec![],
+			free_indices: vec![],
+			size: 0,
+			start_side,
+			end_side,
+		}
+	}
+
+	/// The number of ranges in this list.
+	pub fn size(&self) -> usize {
+		self.size
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.size == 0
+	}
+
+	/// Inserts `range`, which may overlap any other ranges, but must not be empty.
+	///
+	/// This takes time logarithmic in the number of range bounds for every range spanning the start
+	/// or end of `range`, and at least once.
+	///
+	/// # Panics
+	///
+	/// Panics if `range` is empty.
+	pub fn insert_range(&mut self, range: Range<S>, value: T) {
+		assert!(range.start < range.end, "Cannot insert an empty range");
+		let index = match self.free_indices.pop() {
+			Some(index) => {
+				self.values[index] = Some(value);
+				index
+			}
+			None => {
+				self.values.push(Some(value));
+				self.values.len() - 1
+			}
+		};
+		let end_index = self.bound_index(range.end, self.end_side);
+		self.insert_bound(range.end, end_index, NestedRangeNode::End { index });
+		// the start comes before the end, so the end is one bound further back after inserting it
+		let start_index = self.bound_index(range.start, self.start_side);
+		let end_offset = end_index + 1 - start_index;
+		self.insert_bound(range.start, start_index, NestedRangeNode::Start { index, end_offset });
+		self.size += 1;
+	}
+
+	/// Removes a range with exactly the bounds of `range` and returns its value.
+	///
+	/// This takes time logarithmic in the number of range bounds for every range spanning the start
+	/// or end of `range` and every bound at these positions.
+	pub fn remove_range(&mut self, range: Range<S>) -> Option<T> {
+		let end_indices = self.bounds_at(range.end)
+		                      .filter_map(|(node, ..)| match node {
+			                      NestedRangeNode::End { index } => Some(index),
+			                      NestedRangeNode::Start { .. } => None
+		                      })
+		                      .collect::<Vec<_>>();
+		let index = self.remove_bound(range.start, |node| match node {
+			NestedRangeNode::Start { index, .. } => end_indices.contains(&index),
+			NestedRangeNode::End { .. } => false
+		})?;
+		self.remove_bound(range.end, |node| matches!(node, NestedRangeNode::End { index: it } if it == index));
+		self.free_indices.push(index);
+		self.size -= 1;
+		self.values[index].take()
+	}
+
+	/// All ranges containing `position` (including their start, excluding their end) and their
+	/// values, ordered by their start.
+	///
+	/// This takes time logarithmic in the number of range bounds for every returned range, and at
+	/// least once.
+	pub fn ranges_containing(&self, position: S) -> Vec<(Range<S>, &T)> {
+		let count = self.count_up_to(position);
+		self.collect_ranges(count, count)
+	}
+
+	/// All ranges overlapping `range`, that is, starting before its end and ending after its
+	/// start, and their values, ordered by their start.
+	///
+	/// This takes time logarithmic in the number of range bounds for every returned range, and at
+	/// least once.
+	pub fn ranges_overlapping(&self, range: Range<S>) -> Vec<(Range<S>, &T)> {
+		self.collect_ranges(self.list.rank(range.end), self.count_up_to(range.start))
+	}
+
+	/// Inserts space of size `amount` at `position`, moving all range bounds after it, as well as
+	/// the bounds at `position` that stick to the right.
+	///
+	/// Ranges containing `position` grow, as do ranges starting at `position` if their start sticks
+	/// to the left and ranges ending at `position` if their end sticks to the right.
+	pub fn inflate_at(&mut self, position: S, amount: S) {
+		let mut traversal = match self.list.node_at_or_after(position) {
+			Some(traversal) => traversal,
+			None => return
+		};
+		while traversal.position == position && self.sticks_left(*traversal.list.value_at(traversal.node_index)) {
+			if !traversal.has_next() {
+				return;
+			}
+			traversal = traversal.advance();
+		}
+		let (sublist_path, node_index) = (traversal.sublist_path(), traversal.node_index);
+		self.list.inflate_before_node_at_path(&sublist_path, node_index, amount);
+	}
+
+	fn sticks_left(&self, node: NestedRangeNode) -> bool {
+		match node {
+			NestedRangeNode::Start { .. } => self.start_side == StickySide::Left,
+			NestedRangeNode::End { .. } => self.end_side == StickySide::Left
+		}
+	}
+
+	/// The number of range bounds at or before `position`.
+	fn count_up_to(&self, position: S) -> usize {
+		self.list.node_at_or_before(position).map_or(0, |traversal| traversal.node_count_up_to())
+	}
+
+	/// The number of range bounds before a bound inserted at `position` that sticks to `side`.
+	fn bound_index(&self, position: S, side: StickySide) -> usize {
+		match side {
+			StickySide::Left => self.list.rank(position),
+			StickySide::Right => self.count_up_to(position)
+		}
+	}
+
+	/// Inserts `node` at `position`, where it must end up with `bound_index` bounds before it.
+	fn insert_bound(&mut self, position: S, bound_index: usize, node: NestedRangeNode) {
+		self.move_ends_spanning(bound_index, bound_index, 1);
+		if self.sticks_left(node) {
+			self.list.insert_node_in_front_with_value(position, node);
+		} else {
+			self.list.insert(position, node);
+		}
+	}
+
+	/// The nodes at `position` together with their sublist paths, node indices and the number of
+	/// bounds before them.
+	fn bounds_at(&self, position: S) -> impl Iterator<Item = (NestedRangeNode, Vec<usize>, usize, usize)> + '_ {
+		let mut traversal = self.list.node_at_or_after(position).filter(|it| it.position == position);
+		std::iter::from_fn(move || {
+			let current = traversal.take()?;
+			let item = (
+				*current.list.value_at(current.node_index),
+				current.sublist_path(),
+				current.node_index,
+				current.node_count_up_to() - 1,
+			);
+			if current.has_next() {
+				traversal = Some(current.advance()).filter(|it| it.position == position);
+			}
+			Some(item)
+		})
+	}
+
+	/// Removes the first node at `position` for which `predicate` holds and returns the index of
+	/// the range it belongs to.
+	fn remove_bound<P: Fn(NestedRangeNode) -> bool>(&mut self, position: S, predicate: P) -> Option<usize> {
+		let (node, sublist_path, node_index, bound_index) =
+			self.bounds_at(position).find(|(node, ..)| predicate(*node))?;
+		self.move_ends_spanning(bound_index, bound_index + 1, -1);
+		self.list.take_node_at_path(&sublist_path, node_index);
+		match node {
+			NestedRangeNode::Start { index, .. } | NestedRangeNode::End { index } => Some(index)
+		}
+	}
+
+	/// The ranges starting among the first `end` bounds and ending with at least `threshold`
+	/// bounds before them, as the number of bounds before their start, the offset of their end from
+	/// their start and the index of their value, ordered by their start.
+	fn ranges_spanning(&self, end: usize, threshold: usize) -> Vec<(usize, usize, usize)> {
+		let mut ranges = vec![];
+		self.list.visit_nodes_where(
+			end,
+			RangeEnds::empty(),
+			&|before: &RangeEnds, bounds: &RangeEnds| {
+				bounds.last_end.is_some_and(|last_end| before.count + last_end >= threshold)
+			},
+			&mut |before: &RangeEnds, node: &NestedRangeNode| {
+				if let NestedRangeNode::Start { index, end_offset } = *node {
+					ranges.push((before.count, end_offset, index));
+				}
+			},
+		);
+		ranges
+	}
+
+	/// Moves the ends of the ranges returned by [`Self::ranges_spanning`] `amount` bounds further
+	/// from their starts.
+	fn move_ends_spanning(&mut self, end: usize, threshold: usize, amount: isize) {
+		for (start_index, end_offset, index) in self.ranges_spanning(end, threshold) {
+			let traversal = self.list.nth_node(start_index).unwrap();
+			let (sublist_path, node_index) = (traversal.sublist_path(), traversal.node_index);
+			let end_offset = end_offset.checked_add_signed(amount).unwrap();
+			self.list.replace_value_at_path(&sublist_path, node_index, NestedRangeNode::Start { index, end_offset });
+		}
+	}
+
+	/// Collects all ranges returned by [`Self::ranges_spanning`] and their values, ordered by their
+	/// start.
+	fn collect_ranges(&self, end: usize, threshold: usize) -> Vec<(Range<S>, &T)> {
+		let mut ranges = self.ranges_spanning(end, threshold).into_iter()
+			.map(|(start_index, end_offset, index)| {
+				let start = self.list.select(start_index).unwrap();
+				let end = self.list.select(start_index + end_offset).unwrap();
+				(start..end, self.values[index].as_ref().unwrap())
+			})
+			.collect::<Vec<_>>();
+		ranges.sort_by(|(a, _), (b, _)| a.start.cmp(&b.start).then(a.end.cmp(&b.end)));
+		ranges
+	}
+}
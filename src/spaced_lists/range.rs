@@ -0,0 +1,82 @@
+use std::ops::Range;
+
+use num_traits::CheckedAdd;
+
+use crate::spaced_lists::filled_range::FilledRangeSpacedList;
+use crate::spaced_lists::Spacing;
+
+/// A spaced list of non-overlapping, non-empty ranges.
+///
+/// See [`FilledRangeSpacedList`] for a variant that stores a value for every range.
+pub struct RangeSpacedList<S: Spacing> {
+	list: FilledRangeSpacedList<S, ()>,
+}
+
+impl<S: Spacing> Default for RangeSpacedList<S> {
+	fn default() -> Self {
+		Self {
+			list: Default::default(),
+		}
+	}
+}
+
+impl<S: Spacing> RangeSpacedList<S> {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// The number of ranges in this list.
+	pub fn size(&self) -> usize {
+		self.list.size()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.list.is_empty()
+	}
+
+	/// The end of the last range.
+	pub fn length(&self) -> S {
+		self.list.length()
+	}
+
+	/// Inserts `range`, which must neither be empty nor overlap any other range of this list.
+	///
+	/// # Panics
+	///
+	/// Panics if `range` is empty or overlaps another range.
+	pub fn insert_range(&mut self, range: Range<S>) {
+		self.list.insert_range(range, ())
+	}
+
+	/// The range containing `position` (including its start, excluding its end).
+	pub fn range_containing(&self, position: S) -> Option<Range<S>> {
+		self.list.range_containing(position).map(|(range, _)| range)
+	}
+
+	/// Removes the range containing `position` (including its start, excluding its end) and
+	/// returns it.
+	pub fn remove_range_containing(&mut self, position: S) -> Option<Range<S>> {
+		self.list.remove_range_containing(position).map(|(range, _)| range)
+	}
+
+	/// Moves all range starts and ends after `position` `amount` further back. The range
+	/// containing `position`, if there is one, grows by `amount`.
+	pub fn inflate_after(&mut self, position: S, amount: S) {
+		self.list.inflate_after(position, amount)
+	}
+
+	/// Moves all range starts and ends after `position` `amount` further forward. The range
+	/// containing `position`, if there is one, shrinks by `amount`.
+	///
+	/// # Panics
+	///
+	/// Panics if this would move a range start or end to before `position`, or make a range empty.
+	pub fn deflate_after(&mut self, position: S, amount: S) where S: CheckedAdd {
+		self.list.deflate_after(position, amount)
+	}
+
+	/// Iterates over all ranges, in order.
+	pub fn iter(&self) -> impl Iterator<Item = Range<S>> + '_ {
+		self.list.iter().map(|(range, _)| range)
+	}
+}
@@ -0,0 +1,191 @@
+use std::collections::TryReserveError;
+use std::iter;
+use std::marker::PhantomData;
+
+use num_traits::zero;
+
+use crate::spaced_lists::Spacing;
+use crate::spaced_lists::aggregated::Aggregate;
+use crate::spaced_lists::memory::MemoryUsage;
+
+/// Aggregates of the nodes each link of a skeleton leads past, with the same layout as its link
+/// lengths.
+///
+/// Unlike distances and node counts, aggregates can't be subtracted from each other, so the
+/// aggregate every link would have if it were of degree zero (its element) is stored as well, and
+/// the aggregate of a link of higher degree is recombined from the links it spans when one of them
+/// changes.
+#[derive(Eq, PartialEq)]
+pub(crate) struct LinkAggregates<T, A: Aggregate<T>> {
+	elements: Vec<A>,
+	aggregates: Vec<A>,
+	phantom: PhantomData<T>,
+}
+
+impl<T, A: Aggregate<T>> Default for LinkAggregates<T, A> {
+	fn default() -> Self {
+		Self {
+			elements: vec![],
+			aggregates: vec![],
+			phantom: PhantomData,
+		}
+	}
+}
+
+impl<T, A: Aggregate<T>> LinkAggregates<T, A> {
+	/// Creates aggregates for links with the given `elements`, whose number must be zero or a power
+	/// of two.
+	pub(crate) fn from_elements(elements: Vec<A>) -> Self {
+		let mut aggregates = Self {
+			aggregates: elements.clone(),
+			elements,
+			phantom: PhantomData,
+		};
+		for link_index in 0..aggregates.capacity() {
+			aggregates.aggregates[link_index] = aggregates.combine_spanned(link_index);
+		}
+		aggregates
+	}
+
+	pub(crate) fn capacity(&self) -> usize {
+		self.elements.len()
+	}
+
+	/// The aggregate of all links.
+	pub(crate) fn total(&self) -> A {
+		self.aggregates.last().cloned().unwrap_or_else(A::empty)
+	}
+
+	/// Doubles the capacity, or increases it to one if it is zero, like
+	/// [`SpacedListSkeleton::grow`](super::SpacedListSkeleton::grow).
+	pub(crate) fn grow(&mut self) {
+		if self.elements.is_empty() {
+			self.elements.push(A::empty());
+			self.aggregates.push(A::empty());
+		} else {
+			let total = self.total();
+			let capacity = self.capacity();
+			self.elements.extend(iter::repeat_with(A::empty).take(capacity));
+			self.aggregates.extend(iter::repeat_with(A::empty).take(capacity - 1));
+			self.aggregates.push(total);
+		}
+	}
+
+	/// Reserves the memory needed to grow the capacity up to `capacity` without allocating.
+	pub(crate) fn try_reserve_capacity(&mut self, capacity: usize) -> Result<(), TryReserveError> {
+		let additional = capacity.saturating_sub(self.capacity());
+		self.elements.try_reserve_exact(additional)?;
+		self.aggregates.try_reserve_exact(additional)
+	}
+
+	/// Adds the memory allocated for the links to `usage`, counting the ones after the first `size`
+	/// as wasted.
+	pub(crate) fn add_memory_usage(&self, size: usize, usage: &mut MemoryUsage) {
+		usage.add_vec(&self.elements, size);
+		usage.add_vec(&self.aggregates, size);
+	}
+
+	/// Halves the capacity, dropping the upper half of the links, whose elements must be empty,
+	/// like [`SpacedListSkeleton::shrink`](super::SpacedListSkeleton::shrink).
+	pub(crate) fn shrink(&mut self) {
+		let capacity = self.capacity() / 2;
+		self.elements.truncate(capacity);
+		self.elements.shrink_to_fit();
+		self.aggregates.truncate(capacity);
+		self.aggregates.shrink_to_fit();
+	}
+
+	/// Sets the element of the link at `link_index` and updates the aggregates of all links
+	/// spanning it.
+	///
+	/// # Panics
+	///
+	/// Panics when `link_index` is out of bounds.
+	pub(crate) fn set_element(&mut self, link_index: usize, element: A) {
+		self.elements[link_index] = element;
+		let mut link_index = link_index;
+		while link_index < self.capacity() {
+			self.aggregates[link_index] = self.combine_spanned(link_index);
+			link_index |= link_index + 1;
+		}
+	}
+
+	/// The aggregate of the link at `link_index`.
+	pub(crate) fn aggregate_at(&self, link_index: usize) -> &A {
+		&self.aggregates[link_index]
+	}
+
+	/// The elements of all links.
+	pub(crate) fn elements(&self) -> &[A] {
+		&self.elements
+	}
+
+	/// The first link for which `predicate` holds for the combined elements of all links up to and
+	/// including it, or the capacity if there is none. If `predicate` holds for some link, it must
+	/// hold for all links after it.
+	pub(crate) fn first_link_where<P: Fn(&A) -> bool>(&self, predicate: P) -> usize {
+		let capacity = self.capacity();
+		let depth = if capacity == 0 { 0 } else { capacity.trailing_zeros() + 1 };
+		let mut link_index = 0;
+		let mut aggregate = A::empty();
+		for degree in (0..depth).rev() {
+			let next_index = link_index + (1 << degree);
+			if next_index <= capacity {
+				let next_aggregate = aggregate.combine(&self.aggregates[next_index - 1]);
+				if !predicate(&next_aggregate) {
+					link_index = next_index;
+					aggregate = next_aggregate;
+				}
+			}
+		}
+		link_index
+	}
+
+	/// The combined elements of the links from `start` up to, but excluding, `end`.
+	pub(crate) fn aggregate_between(&self, start: usize, end: usize) -> A {
+		let mut aggregate = A::empty();
+		let mut end = end;
+		while end > start {
+			let link_index = end - 1;
+			// the first degree-zero link spanned by the link at link_index
+			let first_spanned = end & link_index;
+			if first_spanned >= start {
+				aggregate = self.aggregates[link_index].combine(&aggregate);
+				end = first_spanned;
+			} else {
+				aggregate = self.elements[link_index].combine(&aggregate);
+				end = link_index;
+			}
+		}
+		aggregate
+	}
+
+	/// The aggregate of the link at `link_index`, combined from the aggregates of the links of
+	/// lower degree it spans and its own element.
+	fn combine_spanned(&self, link_index: usize) -> A {
+		let degree = link_index.trailing_ones();
+		(0..degree).rev()
+			.map(|degree| &self.aggregates[link_index - (1 << degree)])
+			.chain(iter::once(&self.elements[link_index]))
+			.fold(A::empty(), |aggregate, next| aggregate.combine(next))
+	}
+}
+
+/// The largest distance between two neighbouring nodes of a sequence of nodes, which is zero for
+/// less than two nodes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct LargestGap<S>(pub(crate) S);
+
+impl<S: Spacing> Aggregate<S> for LargestGap<S> {
+	fn empty() -> Self {
+		Self(zero())
+	}
+
+	fn of(gap: &S) -> Self {
+		Self(*gap)
+	}
+
+	fn combine(&self, other: &Self) -> Self {
+		Self(self.0.max(other.0))
+	}
+}
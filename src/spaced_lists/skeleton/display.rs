@@ -1,11 +1,6 @@
 use std::collections::HashMap;
-use std::default::default;
-use std::fmt::{Debug, Display, Error, Formatter, Write};
+use std::fmt::{Debug, Display, Formatter, Write};
 use std::io::Write as IOWrite;
-use std::iter;
-use std::ops::Neg;
-
-use num_traits::zero;
 
 use crate::custom_fmt::{CustomFormat, CustomFormatWrapper};
 use crate::spaced_lists::skeleton::SpacedListSkeleton;
@@ -33,13 +28,20 @@ impl Default for SkeletonFormatOptions {
 	}
 }
 
-impl<S: Spacing + Display, Sub: SpacedList<S>> SpacedListSkeleton<S, Sub> {
-	pub(crate) fn highlighted_format(&self, links: Vec<usize>, nodes: Vec<usize>) -> CustomFormatWrapper<Self> {
-		self.custom_format(SkeletonFormatOptions {
-			highlighted_links: links,
-			highlighted_nodes: nodes,
-			..default()
-		})
+impl<S: Spacing, Sub: SpacedList<S>> SpacedListSkeleton<S, Sub> {
+	/// A diagram of this skeleton and its sublists highlighting `links` and `nodes`. This leaves
+	/// out the link lengths, so that it works for positions that can't be displayed.
+	pub(crate) fn highlighted_format(&self, links: Vec<usize>, nodes: Vec<usize>) -> impl Debug + '_ {
+		Diagram {
+			skeleton: self,
+			options: SkeletonFormatOptions {
+				show_link_lengths: false,
+				highlighted_links: links,
+				highlighted_nodes: nodes,
+				..Default::default()
+			},
+			link_length: |_| String::new(),
+		}
 	}
 }
 
@@ -47,6 +49,28 @@ impl<S: Spacing + Display, Sub: SpacedList<S>> CustomFormat for SpacedListSkelet
 	type Options = SkeletonFormatOptions;
 
 	fn fmt(&self, f: &mut Formatter<'_>, options: &SkeletonFormatOptions) -> std::fmt::Result {
+		self.fmt_diagram(f, options, |length| length.to_string())
+	}
+}
+
+/// A skeleton formatted with `options`, writing link lengths with `link_length`.
+struct Diagram<'a, S: Spacing, Sub: SpacedList<S>> {
+	skeleton: &'a SpacedListSkeleton<S, Sub>,
+	options: SkeletonFormatOptions,
+	link_length: fn(S) -> String,
+}
+
+impl<S: Spacing, Sub: SpacedList<S>> Debug for Diagram<'_, S, Sub> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		self.skeleton.fmt_diagram(f, &self.options, self.link_length)
+	}
+}
+
+impl<S: Spacing, Sub: SpacedList<S>> SpacedListSkeleton<S, Sub> {
+	/// Writes the diagram of [`CustomFormat::fmt`], using `link_length` to write the lengths of
+	/// links if `options` show them.
+	fn fmt_diagram(&self, f: &mut Formatter<'_>, options: &SkeletonFormatOptions, link_length: fn(S) -> String)
+		-> std::fmt::Result {
 		let depth = self.depth();
 		let capacity = self.capacity();
 
@@ -59,13 +83,13 @@ impl<S: Spacing + Display, Sub: SpacedList<S>> CustomFormat for SpacedListSkelet
 			if degree == depth - 1 {
 				if options.highlighted_links.contains(&(self.capacity() - 1)) {
 					if options.show_link_lengths {
-						write!(f, "┍━{:━^width$}┑", self.length(), width = (1 << degree) * 4 - 2)?;
+						write!(f, "┍━{:━^width$}┑", link_length(self.length()), width = (1 << degree) * 4 - 2)?;
 					} else {
 						write!(f, "┍━{:━^width$}┑", "", width = (1 << degree) * 4 - 2)?;
 					}
 				} else {
 					if options.show_link_lengths {
-						write!(f, "╭─{:─^width$}╮", self.length(), width = (1 << degree) * 4 - 2)?;
+						write!(f, "╭─{:─^width$}╮", link_length(self.length()), width = (1 << degree) * 4 - 2)?;
 					} else {
 						write!(f, "╭─{:─^width$}╮", "", width = (1 << degree) * 4 - 2)?;
 					}
@@ -73,23 +97,23 @@ impl<S: Spacing + Display, Sub: SpacedList<S>> CustomFormat for SpacedListSkelet
 			} else {
 				for index in 0..(1 << (depth - degree - 2)) {
 					let link_index = (2 << degree) * index + (1 << degree) - 1;
-					let link_length = self.get_link_length_at(link_index);
+					let length = link_length(self.get_link_length_at(link_index));
 					if options.highlighted_links.contains(&link_index) {
 						if options.show_link_lengths {
-							if format!("{}", link_length).len() >= 3 {
-								write!(f, "┝{:━^width$}┑", link_length, width = (1 << degree) * 4 - 1)?;
+							if length.len() >= 3 {
+								write!(f, "┝{:━^width$}┑", length, width = (1 << degree) * 4 - 1)?;
 							} else {
-								write!(f, "┝━{:━^width$}┑", link_length, width = (1 << degree) * 4 - 2)?;
+								write!(f, "┝━{:━^width$}┑", length, width = (1 << degree) * 4 - 2)?;
 							}
 						} else {
 							write!(f, "┝━{:━^width$}┑", "", width = (1 << degree) * 4 - 2)?;
 						}
 					} else {
 						if options.show_link_lengths {
-							if format!("{}", link_length).len() >= 3 {
-								write!(f, "├{:─^width$}╮", link_length, width = (1 << degree) * 4 - 1)?;
+							if length.len() >= 3 {
+								write!(f, "├{:─^width$}╮", length, width = (1 << degree) * 4 - 1)?;
 							} else {
-								write!(f, "├─{:─^width$}╮", link_length, width = (1 << degree) * 4 - 2)?;
+								write!(f, "├─{:─^width$}╮", length, width = (1 << degree) * 4 - 2)?;
 							}
 						} else {
 							write!(f, "├─{:─^width$}╮", "", width = (1 << degree) * 4 - 2)?;
@@ -141,7 +165,11 @@ impl<S: Spacing + Display, Sub: SpacedList<S>> CustomFormat for SpacedListSkelet
 					                             sublist_options: HashMap::new(),
 					                             ..options.clone()
 				                             });
-				write!(vec, "{:?}", sublist.skeleton().custom_format(sublist_options));
+				write!(vec, "{:?}", Diagram {
+					skeleton: sublist.skeleton(),
+					options: sublist_options,
+					link_length,
+				});
 				let string = String::from_utf8(vec).unwrap();
 				for line in string.lines() {
 					writeln!(f, " │ {}", line)?;
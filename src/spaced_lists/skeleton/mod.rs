@@ -1,18 +1,33 @@
-use std::fmt::{Debug, Display, Error, Formatter, Write};
-use std::io::Write as IOWrite;
-use std::iter;
-use std::marker::PhantomData;
-use std::ops::Neg;
+use std::{iter, mem};
+use std::collections::TryReserveError;
+use std::ops::{AddAssign, SubAssign};
 
 use num_traits::zero;
 
-use crate::spaced_lists::{CrateSpacedList, SpacedList};
+use crate::spaced_lists::CrateSpacedList;
 use crate::spaced_lists::Spacing;
+use crate::spaced_lists::error::ViolationKind;
+use crate::spaced_lists::memory::MemoryUsage;
+use crate::spaced_lists::skeleton::aggregates::{LargestGap, LinkAggregates};
 
 #[derive(Eq, PartialEq)]
 pub struct SpacedListSkeleton<S: Spacing, Sub: CrateSpacedList<S>> {
 	link_lengths: Vec<S>,
+	/// The number of nodes each link leads past, which are the nodes after its start node up to and
+	/// including its end node, counting the nodes of sublists. Has the same layout as
+	/// `link_lengths`.
+	node_counts: Vec<usize>,
+	/// The largest distance between two neighbouring nodes each link leads past, or the start node
+	/// of the link and the node after it, counting the nodes of sublists. Has the same layout as
+	/// `link_lengths`. The link from node zero always stores zero, as node zero is not an actual
+	/// node.
+	largest_gaps: LinkAggregates<S, LargestGap<S>>,
 	sublists: Vec<Option<Sub>>,
+	/// Whether the slot of each node is vacant, which it is when the node was removed, but the
+	/// nodes after it haven't been moved one index down yet, see [`Self::vacate_at`]. Has the
+	/// same layout as `sublists`.
+	vacant: Vec<bool>,
+	vacancy_count: usize,
 }
 
 mod display;
@@ -21,12 +36,43 @@ impl<S: Spacing, Sub: CrateSpacedList<S>> Default for SpacedListSkeleton<S, Sub>
 	fn default() -> Self {
 		Self {
 			link_lengths: vec![],
+			node_counts: vec![],
+			largest_gaps: Default::default(),
 			sublists: vec![],
+			vacant: vec![],
+			vacancy_count: 0,
 		}
 	}
 }
 
 impl<S: Spacing, Sub: CrateSpacedList<S>> SpacedListSkeleton<S, Sub> {
+	/// Creates a skeleton without sublists for nodes at `positions`, which must be sorted, with the
+	/// smallest capacity they fit into. This takes linear time, as all link lengths are calculated
+	/// in a single pass.
+	pub(crate) fn from_sorted_positions(positions: &[S]) -> Self {
+		let size = positions.len();
+		let capacity = if size == 0 { 0 } else { size.next_power_of_two() };
+		let mut previous = zero();
+		let distances = positions.iter()
+			.map(|&position| position - mem::replace(&mut previous, position))
+			.chain(iter::repeat_with(zero))
+			.take(capacity)
+			.collect::<Vec<_>>();
+		let node_counts = (0..capacity).map(|link_index| usize::from(link_index < size)).collect();
+		// the first distance is the one from node zero, which is not an actual node
+		let largest_gaps = distances.iter().enumerate()
+			.map(|(link_index, &distance)| LargestGap(if link_index == 0 { zero() } else { distance }))
+			.collect();
+		Self {
+			link_lengths: from_degree_zero(distances),
+			node_counts: from_degree_zero(node_counts),
+			largest_gaps: LinkAggregates::from_elements(largest_gaps),
+			sublists: iter::repeat_with(|| None).take(capacity).collect(),
+			vacant: vec![false; capacity],
+			vacancy_count: 0,
+		}
+	}
+
 	/// # Panics
 	///
 	/// Panics when `index` is out of bounds.
@@ -55,6 +101,28 @@ impl<S: Spacing, Sub: CrateSpacedList<S>> SpacedListSkeleton<S, Sub> {
 		&mut self.sublists[index]
 	}
 
+	/// The sublist of the node at `index`, or `None` if it doesn't have one or `index` is out of
+	/// bounds.
+	pub(crate) fn sublist_at(&self, index: usize) -> Option<&Sub> {
+		self.sublists.get(index)?.as_ref()
+	}
+
+	/// The sublist of the node at `index`, or `None` if it doesn't have one or `index` is out of
+	/// bounds.
+	pub(crate) fn sublist_at_mut(&mut self, index: usize) -> Option<&mut Sub> {
+		self.sublists.get_mut(index)?.as_mut()
+	}
+
+	/// All sublists of this skeleton, in order.
+	pub(crate) fn sublists(&self) -> impl Iterator<Item = &Sub> {
+		self.sublists.iter().flatten()
+	}
+
+	/// All sublists of this skeleton, in order.
+	pub(crate) fn sublists_mut(&mut self) -> impl Iterator<Item = &mut Sub> {
+		self.sublists.iter_mut().flatten()
+	}
+
 	/// # Panics
 	///
 	/// Panics when `index` is out of bounds.
@@ -92,19 +160,64 @@ impl<S: Spacing, Sub: CrateSpacedList<S>> SpacedListSkeleton<S, Sub> {
 
 	/// Doubles this lists capacity, or increase it to one if it is zero.
 	pub(crate) fn grow(&mut self) {
+		self.largest_gaps.grow();
 		if self.link_lengths.is_empty() {
 			self.link_lengths.push(zero());
+			self.node_counts.push(0);
 			self.sublists.push(None);
+			self.vacant.push(false);
 		} else {
 			let length = self.length();
+			let node_count = self.node_count();
 			self.sublists.extend(iter::repeat_with(|| None).take(self.capacity()));
+			self.vacant.extend(iter::repeat_n(false, self.capacity()));
+			self.node_counts.extend(iter::repeat_n(0, self.capacity() - 1));
+			self.node_counts.push(node_count);
 			self.link_lengths.extend(iter::repeat_with(|| S::zero()).take(self.capacity() - 1));
 			self.link_lengths.push(length);
 		}
 	}
 
+	/// Reserves the memory needed to grow this list's capacity up to `capacity` without
+	/// allocating, returning an error if it can't be allocated.
+	pub(crate) fn try_reserve_capacity(&mut self, capacity: usize) -> Result<(), TryReserveError> {
+		let additional = capacity.saturating_sub(self.capacity());
+		self.link_lengths.try_reserve_exact(additional)?;
+		self.node_counts.try_reserve_exact(additional)?;
+		self.largest_gaps.try_reserve_capacity(capacity)?;
+		self.sublists.try_reserve_exact(additional)?;
+		self.vacant.try_reserve_exact(additional)
+	}
+
+	/// Adds the memory allocated by this skeleton to `usage`, counting the links after the first
+	/// `size` ones as wasted. Sublists are only counted by the bytes they take up inline.
+	pub(crate) fn add_memory_usage(&self, size: usize, usage: &mut MemoryUsage) {
+		usage.add_vec(&self.link_lengths, size);
+		usage.add_vec(&self.node_counts, size);
+		self.largest_gaps.add_memory_usage(size, usage);
+		usage.add_vec(&self.sublists, size);
+		usage.add_vec(&self.vacant, size);
+	}
+
+	/// Halves this list's capacity, or decreases it to zero if it is one, dropping the upper half
+	/// of its links, which must not lead to any nodes. The link lengths and node counts of the
+	/// lower half stay valid, as none of these links span the upper half.
+	pub(crate) fn shrink(&mut self) {
+		let capacity = self.capacity() / 2;
+		self.largest_gaps.shrink();
+		self.link_lengths.truncate(capacity);
+		self.link_lengths.shrink_to_fit();
+		self.node_counts.truncate(capacity);
+		self.node_counts.shrink_to_fit();
+		self.sublists.truncate(capacity);
+		self.sublists.shrink_to_fit();
+		self.vacant.truncate(capacity);
+		self.vacant.shrink_to_fit();
+	}
+
 	/// Inflates the link at the specified index.
 	pub(crate) fn inflate_at(&mut self, link_index: usize, amount: S) {
+		let changed_index = link_index;
 		let mut link_index = link_index;
 		for degree in 0..self.depth() {
 			if (link_index >> degree) & 1 == 0 {
@@ -112,14 +225,340 @@ impl<S: Spacing, Sub: CrateSpacedList<S>> SpacedListSkeleton<S, Sub> {
 				link_index += 1 << degree;
 			}
 		}
+		self.update_largest_gap_at(changed_index);
+	}
+
+	/// Inverse of [`Self::inflate_at`]
+	pub(crate) fn deflate_at(&mut self, link_index: usize, amount: S) {
+		let changed_index = link_index;
+		let mut link_index = link_index;
+		for degree in 0..self.depth() {
+			if (link_index >> degree) & 1 == 0 {
+				*self.get_link_length_at_mut(link_index) -= amount;
+				link_index += 1 << degree;
+			}
+		}
+		self.update_largest_gap_at(changed_index);
+	}
+
+	/// Adds `count` to the number of nodes the link at `link_index` leads past, see
+	/// [`Self::inflate_at`].
+	pub(crate) fn add_nodes_at(&mut self, link_index: usize, count: usize) {
+		let mut link_index = link_index;
+		for degree in 0..self.depth() {
+			if (link_index >> degree) & 1 == 0 {
+				self.node_counts[link_index] += count;
+				link_index += 1 << degree;
+			}
+		}
+	}
+
+	/// Inverse of [`Self::add_nodes_at`]
+	pub(crate) fn remove_nodes_at(&mut self, link_index: usize, count: usize) {
+		let mut link_index = link_index;
+		for degree in 0..self.depth() {
+			if (link_index >> degree) & 1 == 0 {
+				self.node_counts[link_index] -= count;
+				link_index += 1 << degree;
+			}
+		}
+	}
+
+	/// The number of nodes in this skeleton and all of its sublists.
+	pub(crate) fn node_count(&self) -> usize {
+		*self.node_counts.last().unwrap_or(&0)
+	}
+
+	/// The number of nodes up to and including the node at `node_index`, counting the nodes of the
+	/// sublists of the nodes before it, but not the ones of its own sublist.
+	///
+	/// # Panics
+	///
+	/// Panics when `node_index` is greater than the capacity.
+	pub(crate) fn get_node_count_up_to(&self, node_index: usize) -> usize {
+		let mut count = 0;
+		let mut node_index = node_index;
+		while node_index > 0 {
+			count += self.node_counts[node_index - 1];
+			node_index &= node_index - 1;
+		}
+		count
+	}
+
+	/// The last node of at most `size` nodes for which [`Self::get_node_count_up_to`] is at most
+	/// `count`, together with that node count.
+	pub(crate) fn last_node_with_count_up_to_at_most(&self, size: usize, count: usize) -> (usize, usize) {
+		let mut node_index = 0;
+		let mut node_count = 0;
+		for degree in (0..self.depth()).rev() {
+			let next_index = node_index + (1 << degree);
+			if next_index <= size && node_count + self.node_counts[next_index - 1] <= count {
+				node_index = next_index;
+				node_count += self.node_counts[next_index - 1];
+			}
+		}
+		(node_index, node_count)
+	}
+
+	/// The largest distance between two neighbouring nodes of this skeleton and its sublists.
+	pub(crate) fn largest_gap(&self) -> S {
+		self.largest_gaps.total().0
+	}
+
+	/// The first link leading past two neighbouring nodes, or from its start node to the node after
+	/// it, that are at least `size` apart, counting the nodes of sublists. `size` must be positive.
+	pub(crate) fn first_link_with_gap_at_least(&self, size: S) -> Option<usize> {
+		let link_index = self.largest_gaps.first_link_where(|gap| gap.0 >= size);
+		(link_index < self.capacity()).then_some(link_index)
+	}
+
+	/// The largest gaps the links would lead past if they were of degree zero, see
+	/// [`Self::link_node_counts`].
+	pub(crate) fn link_largest_gaps(&self) -> Vec<S> {
+		self.largest_gaps.elements().iter().map(|gap| gap.0).collect()
+	}
+
+	/// The largest distance between two neighbouring nodes from the node at `link_index` up to the
+	/// node after it, including the nodes of its sublist, given the `distance` between these nodes.
+	pub(crate) fn largest_gap_in_link(&self, link_index: usize, distance: S) -> S {
+		if link_index == 0 {
+			return zero();
+		}
+		match self.sublist_at(link_index) {
+			Some(sublist) if sublist.skeleton().node_count() > 0 => {
+				let sublist = sublist.skeleton();
+				let gap_before = sublist.get_node_position_at(1);
+				let sublist_length = sublist.length();
+				// while a list is being changed, its sublists may temporarily stick out of their link
+				let gap_after = if distance > sublist_length { distance - sublist_length } else { zero() };
+				gap_before.max(sublist.largest_gap()).max(gap_after)
+			}
+			_ => distance
+		}
+	}
+
+	/// Recalculates the largest gap of the link at `link_index`, which must be done whenever the
+	/// distance it spans or the nodes of the sublist of its start node change.
+	pub(crate) fn update_largest_gap_at(&mut self, link_index: usize) {
+		let gap = self.largest_gap_in_link(link_index, self.get_distance_at(link_index));
+		self.largest_gaps.set_element(link_index, LargestGap(gap));
+	}
+
+	/// The position of the node at `node_index`, relative to node zero.
+	///
+	/// # Panics
+	///
+	/// Panics when `node_index` is greater than the capacity.
+	pub(crate) fn get_node_position_at(&self, node_index: usize) -> S {
+		let mut position = zero();
+		let mut node_index = node_index;
+		while node_index > 0 {
+			position += self.get_link_length_at(node_index - 1);
+			node_index &= node_index - 1;
+		}
+		position
+	}
+
+	/// The distance between the node at `link_index` and the node after it, which is the length
+	/// the link at `link_index` would have if it were of degree zero.
+	///
+	/// # Panics
+	///
+	/// Panics when `link_index` is out of bounds.
+	pub(crate) fn get_distance_at(&self, link_index: usize) -> S {
+		let mut distance = self.get_link_length_at(link_index);
+		let mut degree = 0;
+		while (link_index >> degree) & 1 == 1 {
+			distance -= self.get_link_length_at(link_index - (1 << degree));
+			degree += 1;
+		}
+		distance
+	}
+
+	/// The distances between all neighbouring nodes, see [`Self::get_distance_at`].
+	pub(crate) fn distances(&self) -> Vec<S> {
+		to_degree_zero(self.link_lengths.clone())
+	}
+
+	/// Checks the invariants of this skeleton that don't concern the contents of its sublists,
+	/// given that it has `size` slots.
+	pub(crate) fn check_invariants(&self, size: usize) -> Result<(), ViolationKind> {
+		let capacity = self.capacity();
+		if self.sublists.len() != capacity || self.node_counts.len() != capacity || self.vacant.len() != capacity
+			|| !(capacity == 0 || capacity.is_power_of_two()) {
+			return Err(ViolationKind::InvalidCapacity);
+		}
+		if size > capacity || self.vacant.iter().filter(|&&vacant| vacant).count() != self.vacancy_count {
+			return Err(ViolationKind::InvalidSize);
+		}
+		for (node_index, &vacant) in self.vacant.iter().enumerate() {
+			if vacant && (node_index == 0 || node_index >= size || self.sublists[node_index].is_some()
+				|| self.get_distance_at(node_index) != zero()) {
+				return Err(ViolationKind::InvalidVacancy { node_index });
+			}
+		}
+		// the distance from node zero to the first node may be negative
+		for (link_index, distance) in self.distances().into_iter().enumerate() {
+			if link_index >= size && distance != zero() {
+				return Err(ViolationKind::DistanceAfterLastNode { link_index });
+			}
+			if link_index > 0 && distance < zero() {
+				return Err(ViolationKind::NegativeDistance { link_index });
+			}
+		}
+		Ok(())
 	}
 
-	/// Negation of [`Self::inflate_at`]
-	pub(crate) fn deflate_at(&mut self, link_index: usize, amount: S) where S: Neg<Output = S> {
-		self.inflate_at(link_index, -amount)
+	/// The number of nodes each link would lead past if it were of degree zero, which is the number
+	/// of nodes in the sublist of its start node, plus one for its end node if that exists.
+	pub(crate) fn link_node_counts(&self) -> Vec<usize> {
+		to_degree_zero(self.node_counts.clone())
+	}
+
+	/// Whether the slot of the node at `node_index` is vacant, see [`Self::vacate_at`].
+	pub(crate) fn is_vacant(&self, node_index: usize) -> bool {
+		self.vacant.get(node_index).copied().unwrap_or(false)
+	}
+
+	/// The number of vacant slots, see [`Self::vacate_at`].
+	pub(crate) fn vacancy_count(&self) -> usize {
+		self.vacancy_count
+	}
+
+	/// The index of the first node after the node at `node_index`, skipping vacant slots. There
+	/// must be such a node.
+	pub(crate) fn next_occupied(&self, node_index: usize) -> usize {
+		if !self.is_vacant(node_index + 1) {
+			return node_index + 1;
+		}
+		// vacant slots don't add to the node count, so the next node is the first one after the
+		// sublist of the node at node_index that does
+		let count = self.get_node_count_up_to(node_index + 1);
+		self.last_node_with_count_up_to_at_most(self.capacity(), count).0 + 1
+	}
+
+	/// The index of the last node before the node at `node_index`, which must not be zero,
+	/// skipping vacant slots. This is zero if there is no such node.
+	pub(crate) fn previous_occupied(&self, node_index: usize) -> usize {
+		if !self.is_vacant(node_index - 1) {
+			return node_index - 1;
+		}
+		// the vacant slots before node_index have no sublists, so the nodes counted up to them are
+		// the ones up to the previous node, plus the ones in its sublist
+		let count = self.get_node_count_up_to(node_index - 1);
+		if count == 0 {
+			return 0;
+		}
+		let (before, _) = self.last_node_with_count_up_to_at_most(self.capacity(), count - 1);
+		if self.is_vacant(before + 1) { before } else { before + 1 }
+	}
+
+	/// Removes the node at `node_index` by leaving its slot vacant: the link before it is
+	/// extended to the node after it, and its own link gets a length of zero, so the vacant slot
+	/// is at the position of the node after it. The indices of all other nodes stay the same, so
+	/// unlike moving the nodes after it one index down, this takes logarithmic time.
+	///
+	/// The node must not be node zero, must be followed by another node and must not have a sublist.
+	pub(crate) fn vacate_at(&mut self, node_index: usize) {
+		debug_assert!(node_index > 0 && !self.is_vacant(node_index) && self.get_sublist_at(node_index).is_none());
+		let previous_index = self.previous_occupied(node_index);
+		let distance = self.get_distance_at(node_index);
+		self.inflate_at(previous_index, distance);
+		self.deflate_at(node_index, distance);
+		self.remove_nodes_at(node_index - 1, 1);
+		self.vacant[node_index] = true;
+		self.vacancy_count += 1;
+	}
+
+	/// Puts a node into the vacant slot at `node_index`, `distance` before the node after it.
+	/// Inverse of [`Self::vacate_at`].
+	pub(crate) fn occupy_at(&mut self, node_index: usize, distance: S) {
+		debug_assert!(self.is_vacant(node_index));
+		let previous_index = self.previous_occupied(node_index);
+		self.deflate_at(previous_index, distance);
+		self.inflate_at(node_index, distance);
+		self.add_nodes_at(node_index - 1, 1);
+		self.vacant[node_index] = false;
+		self.vacancy_count -= 1;
+	}
+
+	/// Marks the vacant slot at `node_index`, which must be the last slot and no longer be
+	/// counted as such by the list, as not vacant.
+	pub(crate) fn clear_vacancy_at(&mut self, node_index: usize) {
+		debug_assert!(self.is_vacant(node_index));
+		self.vacant[node_index] = false;
+		self.vacancy_count -= 1;
+	}
+
+	/// Moves the nodes of this skeleton down into the vacant slots before them, given that it has
+	/// `size` slots, so that no slots are vacant anymore. This takes time linear in the capacity.
+	pub(crate) fn remove_vacancies(&mut self, size: usize) {
+		if self.vacancy_count == 0 {
+			return;
+		}
+		let capacity = self.capacity();
+		let size = size - self.vacancy_count;
+		let vacant = mem::replace(&mut self.vacant, vec![false; capacity]);
+		// the link from a vacant slot has length zero, as the link to it already leads to the
+		// node after it, so the links from vacant slots can simply be left out
+		let distances = self.distances().into_iter().zip(&vacant)
+			.filter(|(_, &vacant)| !vacant)
+			.map(|(distance, _)| distance)
+			.chain(iter::repeat_with(zero))
+			.take(capacity)
+			.collect();
+		let largest_gaps = self.largest_gaps.elements().iter().zip(&vacant)
+			.filter(|(_, &vacant)| !vacant)
+			.map(|(&gap, _)| gap)
+			.chain(iter::repeat(LargestGap(zero())))
+			.take(capacity)
+			.collect();
+		let sublists = mem::take(&mut self.sublists).into_iter().zip(&vacant)
+			.filter(|(_, &vacant)| !vacant)
+			.map(|(sublist, _)| sublist)
+			.chain(iter::repeat_with(|| None))
+			.take(capacity)
+			.collect::<Vec<_>>();
+		let node_counts = sublists.iter().enumerate()
+			.map(|(link_index, sublist)| {
+				sublist.as_ref().map_or(0, |sublist| sublist.skeleton().node_count()) + usize::from(link_index < size)
+			})
+			.collect();
+		self.link_lengths = from_degree_zero(distances);
+		self.node_counts = from_degree_zero(node_counts);
+		self.largest_gaps = LinkAggregates::from_elements(largest_gaps);
+		self.sublists = sublists;
+		self.vacancy_count = 0;
 	}
 }
 
+/// Converts the values of links of any degree, each of which is the sum of the values of the
+/// degree-zero links it spans, to the values of these degree-zero links.
+fn to_degree_zero<T: Copy + SubAssign>(mut values: Vec<T>) -> Vec<T> {
+	for link_index in (0..values.len()).rev() {
+		let parent_index = link_index | (link_index + 1);
+		if parent_index < values.len() {
+			let value = values[link_index];
+			values[parent_index] -= value;
+		}
+	}
+	values
+}
+
+/// Inverse of [`to_degree_zero`].
+fn from_degree_zero<T: Copy + AddAssign>(mut values: Vec<T>) -> Vec<T> {
+	for link_index in 0..values.len() {
+		let parent_index = link_index | (link_index + 1);
+		if parent_index < values.len() {
+			let value = values[link_index];
+			values[parent_index] += value;
+		}
+	}
+	values
+}
+
+pub(crate) mod aggregates;
+
 pub(crate) mod traversal;
 
 mod tests;
@@ -1,10 +1,5 @@
-use std::mem;
-use std::ops::Deref;
-use std::rc::Rc;
-
 use num_traits::zero;
 
-use crate::spaced_lists::skeleton::SpacedListSkeleton;
 use crate::spaced_lists::SpacedList;
 use crate::spaced_lists::Spacing;
 
@@ -25,7 +20,7 @@ impl<'a, S: Spacing, List: SpacedList<S>> Clone for Traversal<'a, S, List> {
 			node_index: self.node_index,
 			link_index: self.link_index,
 			list: self.list,
-			super_traversal: None,
+			super_traversal: self.super_traversal.clone(),
 		}
 	}
 }
@@ -33,16 +28,43 @@ impl<'a, S: Spacing, List: SpacedList<S>> Clone for Traversal<'a, S, List> {
 // impl<'a, S: Spacing, List: SpacedList<S>> Copy for Traversal<'a, S, List> {}
 
 impl<'a, S: Spacing, List: SpacedList<S>> Traversal<'a, S, List> {
+	/// Creates a traversal at node zero of `list`, which is at position zero, but is not an actual
+	/// node of the list.
 	pub fn new(list: &'a List) -> Self {
 		Self {
-			degree: if list.skeleton().depth() > 0 { list.skeleton().depth() - 1 } else { 0 },
+			degree: 0,
 			position: zero(),
 			node_index: 0,
-			link_index: if list.skeleton().capacity() > 0 { list.skeleton().capacity() - 1 } else { 0 },
+			link_index: 0,
 			list,
 			super_traversal: None,
 		}
 	}
+
+	/// The indices of the nodes whose sublists were descended into to reach the current list,
+	/// starting with the outermost one.
+	pub(crate) fn sublist_path(&self) -> Vec<usize> {
+		let mut path = vec![];
+		let mut traversal = self;
+		while let Some(super_traversal) = &traversal.super_traversal {
+			path.push(super_traversal.node_index);
+			traversal = super_traversal;
+		}
+		path.reverse();
+		path
+	}
+
+	/// The number of nodes up to and including the current one, counting the nodes in sublists,
+	/// which is zero for node zero of the outermost list.
+	pub(crate) fn node_count_up_to(&self) -> usize {
+		let mut count = self.list.skeleton().get_node_count_up_to(self.node_index);
+		let mut traversal = self;
+		while let Some(super_traversal) = &traversal.super_traversal {
+			count += super_traversal.list.skeleton().get_node_count_up_to(super_traversal.node_index);
+			traversal = super_traversal;
+		}
+		count
+	}
 }
 
 mod display;
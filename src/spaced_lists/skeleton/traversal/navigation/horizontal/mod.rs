@@ -1,55 +1,113 @@
-use crate::spaced_lists::skeleton::SpacedListSkeleton;
 use crate::spaced_lists::skeleton::traversal::Traversal;
 use crate::spaced_lists::SpacedList;
 use crate::spaced_lists::Spacing;
+use crate::spaced_lists::error::SpacedListError;
 
 impl<'a, S: Spacing, List: SpacedList<S>> Traversal<'a, S, List> {
-	/// IMPORTANT: After calling this method, `self.link_index` no longer refers to the link length
-	/// between the current node and the next one at `self.degree`, because after calling this,
-	/// there cannot be such an index.
+	/// IMPORTANT: After calling this method, `self.link_index` refers to the link at `self.degree`
+	/// that starts at the new node, which might not exist.
 	pub(in crate::spaced_lists::skeleton::traversal::navigation)
 	fn advance_unchecked(&mut self) {
 		self.position += self.list.skeleton().get_link_length_at(self.link_index);
 		self.node_index += 1 << self.degree;
+		self.link_index += 1 << self.degree;
 	}
 
-	/// ╭───────────────────────────────────────────────────────────────╮
-	/// ├───────────────────────────────╮                               │
-	///C├───────────────╮D              ├───────────────╮               │
-	/// ├───────╮B     E├───────╮       ├───────╮       ├───────╮       │
-	/// ├───╮   ├───╮A F├───╮   ├───╮   ├───╮   ├───╮   ├───╮   ├───╮   │
-	/// ╵ 0 ╵ 1 ╵ 2 ╵ 3 ╵ 4 ╵ 5 ╵ 6 ╵ 7 ╵ 8 ╵ 9 ╵ A ╵ B ╵ C ╵ D ╵ E ╵ F ╵
-	/// Assuming that node_index = 3, and degree = 0,
-	/// in order to advance, one would have to
-	/// unwind to B, see that advance_unchecked is still not an option,
-	/// unwind to C, see that advance_unchecked now is an option,
-	/// advance_unchecked to D,
-	/// descend to E,
-	/// descend to F
-	pub fn advance(mut self) -> Self {
-		if self.node_index == self.list.skeleton().capacity() {
+	/// Moves to the next node of the current list, skipping the sublist of the current node, or, if
+	/// this is the last node of a sublist, to the node after the one the sublist belongs to.
+	///
+	/// # Panics
+	///
+	/// Panics if there is no next node.
+	pub(in crate::spaced_lists::skeleton::traversal::navigation)
+	fn advance_shallow(mut self) -> Self {
+		if self.node_index < self.list.slot_count() {
+			// get_distance_at unwinds the degrees above the link at node_index, which takes
+			// amortized constant time when advancing through all nodes
+			self.position += self.list.skeleton().get_distance_at(self.node_index);
+			self.node_index = self.list.skeleton().next_occupied(self.node_index);
+			self.degree = 0;
+			self.link_index = self.node_index;
+			self
+		} else {
 			match self.super_traversal {
-				Some(super_traversal) => {
-					self = *super_traversal;
-					return self.advance();
-				}
-				None => {
-					panic!("Cannot advance past skeleton capacity")
-				}
+				Some(super_traversal) => super_traversal.advance_shallow(),
+				None => panic!("Cannot advance past the last node")
 			}
 		}
+	}
+
+	/// Whether there is a node after the current one.
+	pub fn has_next(&self) -> bool {
+		// only the last node of the outermost list has no node after it, as the last node of a list
+		// never has a sublist
+		self.can_descend() || self.node_index < self.list.slot_count() || self.super_traversal.is_some()
+	}
+
+	/// Moves to the next node, descending into the sublist of the current node if it has one.
+	///
+	/// # Panics
+	///
+	/// Panics if there is no next node.
+	pub fn advance(self) -> Self {
+		if self.can_descend() {
+			self.descend().advance_shallow()
+		} else {
+			self.advance_shallow()
+		}
+	}
+
+	/// Whether there is a node before the current one, not counting node zero of the outermost
+	/// list.
+	pub fn has_previous(&self) -> bool {
+		// node zero of a sublist is the node the sublist belongs to
+		(self.node_index > 1 && self.list.skeleton().previous_occupied(self.node_index) > 0)
+			|| self.super_traversal.is_some()
+	}
+
+	/// Like [`Self::advance`], but returns an error instead of panicking if there is no next node.
+	pub fn try_advance(self) -> Result<Self, SpacedListError> {
+		if self.has_next() {
+			Ok(self.advance())
+		} else {
+			Err(SpacedListError::NoNextNode)
+		}
+	}
 
-		let degree_before = self.degree;
-		let link_index_before = self.link_index;
-		while self.node_index & (1 << self.degree) > 0 {
-			self.position -= self.list.skeleton().get_link_length_at(self.link_index);
-			self.node_index -= 1 << self.degree;
-			self.link_index -= 1 << self.degree;
-			self.degree += 1;
+	/// Moves to the previous node, which is the last node of the sublist of the node before the
+	/// current one if it has one, or onto node zero if this is the first node.
+	///
+	/// # Panics
+	///
+	/// Panics if the current node is node zero.
+	pub fn retreat(mut self) -> Self {
+		assert!(self.node_index > 0, "Cannot retreat past node zero");
+		let previous_index = self.list.skeleton().previous_occupied(self.node_index);
+		if previous_index == 0 && self.super_traversal.is_some() {
+			return self.ascend();
+		}
+		// the vacant slots in between are at the position of the current node
+		self.node_index = previous_index;
+		self.position -= self.list.skeleton().get_distance_at(self.node_index);
+		self.degree = 0;
+		self.link_index = self.node_index;
+		if self.can_descend() {
+			// the last node of a sublist never has a sublist itself
+			self = self.descend();
+			self.position += self.list.length();
+			self.node_index = self.list.slot_count();
+			self.link_index = self.node_index;
 		}
-		self.advance_unchecked();
-		self.degree = degree_before;
-		self.link_index = link_index_before + (1 << self.degree);
 		self
 	}
-}
\ No newline at end of file
+
+	/// Like [`Self::retreat`], but returns an error instead of panicking if the current node is node
+	/// zero.
+	pub fn try_retreat(self) -> Result<Self, SpacedListError> {
+		if self.node_index > 0 {
+			Ok(self.retreat())
+		} else {
+			Err(SpacedListError::NoPreviousNode)
+		}
+	}
+}
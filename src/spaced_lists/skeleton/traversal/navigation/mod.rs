@@ -1,10 +1,3 @@
-use std::mem;
-use std::ops::Deref;
-use std::rc::Rc;
-
-use num_traits::zero;
-
-use crate::spaced_lists::skeleton::SpacedListSkeleton;
 use crate::spaced_lists::skeleton::traversal::Traversal;
 use crate::spaced_lists::SpacedList;
 use crate::spaced_lists::Spacing;
@@ -18,78 +11,150 @@ mod links;
 mod nodes;
 
 impl<'a, S: Spacing, List: SpacedList<S>> Traversal<'a, S, List> {
+	/// Advances through the nodes of the current list, without descending into sublists, for as
+	/// long as `condition` holds for the node that would be advanced to.
+	///
+	/// `condition` must hold for some (possibly empty) prefix of the nodes, and for no node after
+	/// that prefix.
 	pub fn advance_while_shallow<F: Fn(&Self) -> bool>(&mut self, condition: F) {
-		while self.degree > 0 && self.node_index < self.list.size() - 1 {
-			let super_traversal = self.super_traversal.take();
-			let mut next = self.clone();
-			next.descend_and_advance_shallow();
-			if condition(&next) {
-				*self = next;
-			} else {
-				self.descend_shallow();
-			}
-			self.super_traversal = super_traversal;
+		if self.node_index >= self.list.slot_count() {
+			return;
 		}
-		if self.node_index < self.list.size() - 1 {
-			let mut next = self.clone();
-			next.advance_unchecked();
-			if condition(&next) {
-				*self = next;
+		let super_traversal = self.super_traversal.take();
+		// make ever larger jumps, for as long as they are allowed
+		loop {
+			self.degree = self.max_degree();
+			self.link_index = self.node_index + (1 << self.degree) - 1;
+			if !self.advance_if(&condition) {
+				break;
 			}
 		}
+		// the node we are looking for is before the jump that was not allowed, narrow in on it
+		while self.degree > 0 {
+			self.descend_shallow();
+			self.advance_if(&condition);
+		}
+		self.link_index = self.node_index;
+		self.super_traversal = super_traversal;
 	}
 
+	/// Advances through the nodes of the current list and its sublists for as long as `condition`
+	/// holds for the node that would be advanced to.
+	///
+	/// `condition` must hold for some (possibly empty) prefix of the nodes, and for no node after
+	/// that prefix.
 	pub fn advance_while<F: Fn(&Self) -> bool>(mut self, condition: F) -> Self {
-		// fixme link index overflow error, reproduce by calling node_at with position = length
-		while self.can_descend() && self.node_index < self.list.size() - 1 {
-			let super_traversal = self.super_traversal.take();
-			let mut next = self.clone();
-			next = next.descend_and_advance();
-			if condition(&next) {
-				self = next;
-			} else {
-				self = self.descend();
+		self.advance_while_shallow(&condition);
+		self.advance_through_sublists_while(condition)
+	}
+
+	/// Retreats through the nodes of the current list, without descending into sublists, for as
+	/// long as `condition` holds for the node that would be retreated to, but never onto node zero.
+	///
+	/// `condition` must hold for some (possibly empty) suffix of the nodes before the current one,
+	/// and for no node before that suffix.
+	pub fn retreat_while_shallow<F: Fn(&Self) -> bool>(&mut self, condition: F) {
+		let node_index = self.node_index;
+		let mut last_failing = self.node_zero();
+		last_failing.advance_while_shallow(|traversal| traversal.node_index < node_index && !condition(traversal));
+		let first_holding = last_failing.advance_shallow();
+		if first_holding.node_index < node_index {
+			*self = first_holding;
+		}
+	}
+
+	/// Retreats through the nodes of the current list and its sublists, as well as the lists it is a
+	/// sublist of, for as long as `condition` holds for the node that would be retreated to, but
+	/// never onto node zero.
+	///
+	/// `condition` must hold for some (possibly empty) suffix of the nodes before the current one,
+	/// and for no node before that suffix.
+	pub fn retreat_while<F: Fn(&Self) -> bool>(self, condition: F) -> Self {
+		if self.node_index == 0 {
+			return self;
+		}
+		let mut current = self;
+		loop {
+			let node_index = current.node_index;
+			// find the last node before the current one for which condition doesn't hold, the node
+			// after it is the one we are looking for
+			let mut last_failing = current.node_zero();
+			last_failing.advance_while_shallow(|traversal| traversal.node_index < node_index && !condition(traversal));
+			if last_failing.node_index > 0 {
+				return last_failing.advance_through_sublists_while(|traversal| !condition(traversal)).advance();
+			}
+			// condition holds for all nodes of this list before the current one, continue with the
+			// node this list belongs to, if condition holds for it
+			match last_failing.super_traversal.take() {
+				Some(super_traversal) if condition(&super_traversal) => current = *super_traversal,
+				super_traversal => {
+					last_failing.super_traversal = super_traversal;
+					return last_failing.advance();
+				}
 			}
-			self.super_traversal = super_traversal;
 		}
-		if self.node_index < self.list.size() - 1 {
-			let mut next = self.clone();
-			next.advance_unchecked();
-			if condition(&next) {
-				self = next;
+	}
+
+	/// Descends into the sublist of the current node and advances through it for as long as
+	/// `condition` holds, repeating this for the sublist of the node advanced to.
+	fn advance_through_sublists_while<F: Fn(&Self) -> bool>(mut self, condition: F) -> Self {
+		while self.can_descend() {
+			let mut sub_traversal = self.descend();
+			sub_traversal.advance_while_shallow(&condition);
+			if sub_traversal.node_index == 0 {
+				return sub_traversal.ascend();
 			}
+			self = sub_traversal;
 		}
 		self
 	}
 
-	/// # Panics
-	///
-	/// Panics if `self.degree` is `0`.
-	fn descend_and_advance_shallow(&mut self) {
-		self.degree -= 1;
-		self.position += self.list.skeleton().get_link_length_at(self.link_index);
-		self.node_index += 1 << self.degree;
-		self.link_index += 1 << self.degree;
+	/// A traversal resting on node zero of the current list.
+	fn node_zero(&self) -> Self {
+		let mut traversal = self.clone();
+		traversal.position -= self.list.skeleton().get_node_position_at(self.node_index);
+		traversal.node_index = 0;
+		traversal.degree = 0;
+		traversal.link_index = 0;
+		traversal
 	}
 
-	/// # Panics
+	/// The highest degree of the links that start at the current node.
+	fn max_degree(&self) -> usize {
+		let depth = self.list.skeleton().depth();
+		if self.node_index == 0 {
+			depth - 1
+		} else {
+			(self.node_index.trailing_zeros() as usize).min(depth - 1)
+		}
+	}
+
+	/// Advances along the link at `self.link_index` if that doesn't go past the last node and
+	/// `condition` holds for the node advanced to.
 	///
-	/// Panics if `self.degree` is `0` and there is no sublist to descend into.
-	fn descend_and_advance(mut self) -> Self {
-		if self.degree > 0 {
-			self.descend_and_advance_shallow();
-			self
+	/// A vacant slot is at the position of the node after it, so whether to advance onto it is
+	/// decided by `condition` for that node. Therefore, advancing never ends on a vacant slot.
+	fn advance_if<F: Fn(&Self) -> bool>(&mut self, condition: F) -> bool {
+		if self.node_index + (1 << self.degree) > self.list.slot_count() {
+			return false;
+		}
+		let mut next = self.clone();
+		next.advance_unchecked();
+		let skeleton = self.list.skeleton();
+		let holds = if skeleton.is_vacant(next.node_index) {
+			let mut node = next.clone();
+			node.node_index = skeleton.next_occupied(next.node_index);
+			node.degree = 0;
+			node.link_index = node.node_index;
+			condition(&node)
+		} else {
+			condition(&next)
+		};
+		if holds {
+			*self = next;
+			true
 		} else {
-			let sublist = self.list.skeleton().get_sublist_at(self.node_index).as_ref().unwrap();
-			let new = Traversal {
-				degree: sublist.skeleton().depth() - 1,
-				node_index: 0,
-				link_index: sublist.skeleton().capacity() - 1,
-				position: self.position + sublist.skeleton().get_link_length_at(0),
-				list: sublist,
-				super_traversal: Some(Box::new(self)),
-			};
-			new
+			false
 		}
 	}
-}
\ No newline at end of file
+}
@@ -1,5 +1,4 @@
-use crate::spaced_lists::skeleton::SpacedListSkeleton;
-use crate::spaced_lists::skeleton::traversal::{navigation, Traversal};
+use crate::spaced_lists::skeleton::traversal::Traversal;
 use crate::spaced_lists::SpacedList;
 use crate::spaced_lists::Spacing;
 
@@ -13,30 +12,55 @@ impl<'a, S: Spacing, List: SpacedList<S>> Traversal<'a, S, List> {
 		self.link_index -= 1 << self.degree
 	}
 
+	/// Moves into the sublist of the current node, onto node zero of that sublist, which is at the
+	/// same position as the current node.
+	///
 	/// # Panics
 	///
-	/// Panics if `self.degree` is `0` and there is no sublist to descend into.
+	/// Panics if there is no sublist to descend into.
 	pub(in crate::spaced_lists::skeleton::traversal::navigation)
-	fn descend(mut self) -> Self {
-		if self.degree > 0 {
-			self.descend_shallow();
-			self
-		} else {
-			let sublist = self.list.skeleton().get_sublist_at(self.node_index).as_ref().unwrap();
-			let new = Traversal {
-				degree: sublist.skeleton().depth() - 1,
-				position: self.position,
-				node_index: 0,
-				link_index: sublist.skeleton().capacity() - 1,
-				list: sublist,
-				super_traversal: Some(Box::new(self)),
-			};
-			new
+	fn descend(self) -> Self {
+		let sublist = self.list.skeleton().get_sublist_at(self.node_index).as_ref().unwrap();
+		Traversal {
+			degree: 0,
+			position: self.position,
+			node_index: 0,
+			link_index: 0,
+			list: sublist,
+			super_traversal: Some(Box::new(self)),
 		}
 	}
 
+	/// Moves out of the current sublist, onto the node it belongs to.
+	///
+	/// # Panics
+	///
+	/// Panics if the current list is not a sublist.
+	pub(in crate::spaced_lists::skeleton::traversal::navigation)
+	fn ascend(self) -> Self {
+		*self.super_traversal.unwrap()
+	}
+
 	pub(in crate::spaced_lists::skeleton::traversal::navigation)
 	fn can_descend(&self) -> bool {
-		self.degree > 0 || self.list.skeleton().get_sublist_at(self.node_index).is_some()
+		self.list.skeleton().sublist_at(self.node_index).is_some()
+	}
+
+	/// Creates a traversal resting on the node at `node_index` of the list reached by following
+	/// `sublist_path` (see [`CrateSpacedList::descendant_mut`]).
+	///
+	/// [`CrateSpacedList::descendant_mut`]: crate::spaced_lists::CrateSpacedList::descendant_mut
+	pub(crate) fn at_path(list: &'a List, sublist_path: &[usize], node_index: usize) -> Self {
+		let mut traversal = Self::new(list);
+		for &sublist_index in sublist_path {
+			traversal.position += traversal.list.skeleton().get_node_position_at(sublist_index);
+			traversal.node_index = sublist_index;
+			traversal.link_index = sublist_index;
+			traversal = traversal.descend();
+		}
+		traversal.position += traversal.list.skeleton().get_node_position_at(node_index);
+		traversal.node_index = node_index;
+		traversal.link_index = node_index;
+		traversal
 	}
-}
\ No newline at end of file
+}
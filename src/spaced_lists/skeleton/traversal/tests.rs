@@ -1,14 +1,14 @@
 #![cfg(test)]
 
-use std::default::default;
-use num_traits::real::Real;
-use crate::custom_fmt::CustomFormat;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 use crate::spaced_lists::hollow::HollowSpacedList;
-use crate::spaced_lists::skeleton::display::SkeletonFormatOptions;
-use crate::spaced_lists::skeleton::traversal::Traversal;
 use crate::spaced_lists::{CrateSpacedList, SpacedList};
+use crate::spaced_lists::tests::random_list;
 
 #[test]
+// validating the list after every insertion makes this take quadratic time
+#[cfg_attr(feature = "paranoid", ignore)]
 fn advance_while() {
 	let mut list = HollowSpacedList::<i64>::new();
 	// list.insert_node(2);
@@ -31,4 +31,43 @@ fn advance_while() {
 	// TODO test advance
 	// TODO test advance with sublists
 	// TODO implement higher-level methods on skeletons and lists
-}
\ No newline at end of file
+}
+
+#[test]
+fn node_at_in_list_from_sorted_positions() {
+	let list = HollowSpacedList::from_sorted_positions((0..=1000000).map(|n| n * 2));
+	for n in 0..=1000000 {
+		assert_eq!(list.node_at(n * 2).map(|it| it.position), Some(n * 2));
+		assert_eq!(list.node_at(n * 2 + 1).map(|it| it.position), None);
+	}
+}
+
+#[test]
+fn retreat_and_retreat_while() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let (list, expected) = random_list(&mut rng, 500);
+
+	let mut traversal = list.traversal().advance_while(|_| true);
+	for (index, &position) in expected.iter().enumerate().rev() {
+		assert_eq!(traversal.position, position);
+		assert_eq!(traversal.has_previous(), index > 0);
+		traversal = traversal.retreat();
+	}
+	assert_eq!(traversal.node_index, 0);
+
+	for _ in 0..200 {
+		let from = rng.gen_range(0..expected.len());
+		let bound = rng.gen_range(-110..110);
+		let start = list.traversal().advance_while(|it| it.position <= expected[from]);
+		let traversal = start.retreat_while(|it| it.position >= bound);
+		// the first node at or after bound, unless all nodes before the start are before bound
+		let first = expected.partition_point(|&it| it < bound);
+		let expected_position = if first >= expected.partition_point(|&it| it <= expected[from]) {
+			expected[from]
+		} else {
+			expected[first]
+		};
+		assert_eq!(traversal.position, expected_position);
+	}
+	assert_eq!(list.traversal().advance_while(|_| true).retreat_while(|_| true).position, expected[0]);
+}
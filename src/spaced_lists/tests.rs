@@ -0,0 +1,1075 @@
+#![cfg(test)]
+
+use std::mem::size_of;
+use std::ops::{Bound, RangeBounds};
+use std::time::{Duration, Instant};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use crate::spaced_lists::aggregated::{Aggregate, AggregatedSpacedList};
+use crate::spaced_lists::filled::FilledSpacedList;
+use crate::spaced_lists::filled_range::FilledRangeSpacedList;
+use crate::spaced_lists::hollow::{DuplicatePolicy, HollowSpacedList};
+use crate::spaced_lists::nested_range::{NestedRangeSpacedList, StickySide};
+use crate::spaced_lists::range::RangeSpacedList;
+use crate::spaced_lists::{CrateSpacedList, SpacedList};
+use crate::spaced_lists::error::{InvariantViolation, SpacedListError, ViolationKind};
+use crate::spaced_lists::memory::MemoryUsage;
+
+/// `count` random positions from -100 up to 100, in the order they were generated.
+fn random_positions(rng: &mut StdRng, count: usize) -> Vec<i64> {
+	(0..count).map(|_| rng.gen_range(-100..100)).collect()
+}
+
+/// A list with nodes inserted one after the other at `count` random positions, see
+/// [`random_positions`], together with these positions in order.
+pub(crate) fn random_list(rng: &mut StdRng, count: usize) -> (HollowSpacedList<i64>, Vec<i64>) {
+	let mut positions = random_positions(rng, count);
+	let mut list = HollowSpacedList::new();
+	for &position in &positions {
+		list.insert_node(position);
+	}
+	positions.sort();
+	(list, positions)
+}
+
+/// Like [`random_list`], but the nodes store the order in which they were inserted as values.
+fn random_filled_list(rng: &mut StdRng, count: usize) -> (FilledSpacedList<i64, usize>, Vec<(i64, usize)>) {
+	let positions = random_positions(rng, count);
+	let mut list = FilledSpacedList::new();
+	for (value, &position) in positions.iter().enumerate() {
+		list.insert(position, value);
+	}
+	// nodes are inserted after all nodes at the same position, so a stable sort keeps their order
+	let mut entries = positions.into_iter().zip(0..).collect::<Vec<_>>();
+	entries.sort_by_key(|&(position, _)| position);
+	(list, entries)
+}
+
+fn entries(list: &FilledSpacedList<i64, usize>) -> Vec<(i64, usize)> {
+	list.range_with_values(..).map(|(position, &value)| (position, value)).collect()
+}
+
+#[test]
+fn insert_and_remove() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let (mut list, mut expected) = random_list(&mut rng, 500);
+	assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+	assert_eq!(list.length(), *expected.last().unwrap());
+
+	while !expected.is_empty() {
+		let position = rng.gen_range(-100..100);
+		let removed = list.remove_node_at(position);
+		assert_eq!(removed, expected.contains(&position));
+		if let Some(index) = expected.iter().position(|&it| it == position) {
+			expected.remove(index);
+		}
+		assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+		if let Some(&last) = expected.last() {
+			assert_eq!(list.length(), last);
+		}
+	}
+	assert!(list.is_empty());
+}
+
+#[test]
+fn remove_and_reuse_vacant_slots() {
+	let mut rng = StdRng::seed_from_u64(21);
+	let mut list = FilledSpacedList::<i64, usize>::new();
+	let mut expected = vec![];
+	for value in 0..256 {
+		list.append(1, value);
+		expected.push((value as i64 + 1, value));
+	}
+	// removing any node but the last leaves its slot vacant, so the nodes after it keep theirs
+	assert_eq!(list.remove(1), Some(0));
+	expected.remove(0);
+	while expected.len() > 100 {
+		let index = rng.gen_range(0..expected.len() - 1);
+		assert_eq!(list.remove(expected[index].0), Some(expected.remove(index).1));
+	}
+	assert_eq!(list.size(), 100);
+	assert_eq!(list.capacity(), 256);
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(entries(&list), expected);
+	for (index, &(position, value)) in expected.iter().enumerate() {
+		assert_eq!(list.select(index), Some(position));
+		assert_eq!(list.index_of(position), Some(index));
+		assert_eq!(list[index], value);
+	}
+	let mut cursor = list.cursor_mut();
+	for &(position, _) in &expected {
+		cursor.advance();
+		assert_eq!(cursor.position(), position);
+	}
+	for &(position, _) in expected.iter().rev() {
+		assert_eq!(cursor.position(), position);
+		cursor.retreat();
+	}
+	assert!(cursor.is_at_node_zero() && !cursor.has_previous());
+
+	// a node inserted before the first one takes the vacant slot in front of it
+	list.insert(0, 1000);
+	expected.insert(0, (0, 1000));
+	list.insert(expected[50].0, 1001);
+	expected.insert(51, (expected[50].0, 1001));
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(entries(&list), expected);
+
+	list.shrink_to_fit();
+	assert_eq!(list.capacity(), 128);
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(entries(&list), expected);
+}
+
+#[test]
+#[cfg_attr(feature = "paranoid", ignore)]
+fn remove_node_at_takes_logarithmic_time() {
+	// moving the nodes after a removed one down would take about a million steps per removal
+	let size = 1 << 20;
+	let mut list = HollowSpacedList::<i64>::from_sorted_positions(0..size);
+	let start = Instant::now();
+	for position in 0..10_000 {
+		assert!(list.remove_node_at(position));
+		assert!(list.remove_node_at(size / 2 + position));
+	}
+	let elapsed = start.elapsed();
+	assert!(elapsed < Duration::from_secs(2), "20000 removals took {elapsed:?}");
+	assert_eq!(list.size(), (1 << 20) - 20_000);
+	assert_eq!(list.select(0), Some(10_000));
+	assert_eq!(list.rank(size / 2 + 10_000), size as usize / 2 - 10_000);
+}
+
+#[test]
+fn remove_node_before_and_after() {
+	let mut list = HollowSpacedList::<i64>::new();
+	for position in [10, 2, 7, 5, 9, 3, 3] {
+		list.insert_node(position);
+	}
+	assert_eq!(list.remove_node_before(5), Some(3));
+	assert_eq!(list.remove_node_after(3), Some(5));
+	assert_eq!(list.remove_node_before(2), None);
+	assert_eq!(list.remove_node_after(10), None);
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![2, 3, 7, 9, 10]);
+	assert_eq!(list.node_at(7).map(|it| it.position), Some(7));
+	assert_eq!(list.node_at_or_after(4).map(|it| it.position), Some(7));
+	assert_eq!(list.node_at_or_before(8).map(|it| it.position), Some(7));
+	assert!(list.node_at(5).is_none());
+}
+
+#[test]
+fn inflate_and_deflate_after() {
+	let mut list = HollowSpacedList::<i64>::new();
+	let mut expected = vec![];
+	for position in [0, 20, 10, 5, 15, 12, 12, 3] {
+		list.insert_node(position);
+		let index = expected.partition_point(|&it| it <= position);
+		expected.insert(index, position);
+	}
+
+	list.inflate_after(12, 4);
+	for position in &mut expected {
+		if *position > 12 {
+			*position += 4;
+		}
+	}
+	assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+
+	list.inflate_after(-1, 2);
+	for position in &mut expected {
+		*position += 2;
+	}
+	assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+
+	list.deflate_after(7, 5);
+	for position in &mut expected {
+		if *position > 7 {
+			*position -= 5;
+		}
+	}
+	assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+	assert_eq!(list.length(), *expected.last().unwrap());
+}
+
+#[test]
+#[should_panic]
+fn deflate_after_past_next_node() {
+	let mut list = HollowSpacedList::<i64>::new();
+	list.insert_node(2);
+	list.insert_node(5);
+	list.deflate_after(3, 3);
+}
+
+#[test]
+fn iter() {
+	assert_eq!(HollowSpacedList::<i64>::new().iter().next(), None);
+	let mut rng = StdRng::seed_from_u64(0);
+	let (list, expected) = random_list(&mut rng, 500);
+	assert_eq!(list.deep_size(), expected.len());
+	assert_eq!(list.iter().len(), expected.len());
+	assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+	assert_eq!((&list).into_iter().rev().collect::<Vec<_>>(), expected.iter().rev().copied().collect::<Vec<_>>());
+
+	let mut iter = list.iter();
+	let (mut front, mut back) = (0, expected.len());
+	while front < back {
+		if rng.gen() {
+			assert_eq!(iter.next(), Some(expected[front]));
+			front += 1;
+		} else {
+			back -= 1;
+			assert_eq!(iter.next_back(), Some(expected[back]));
+		}
+		assert_eq!(iter.len(), back - front);
+	}
+	assert_eq!(iter.next(), None);
+	assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn range() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let (list, expected) = random_list(&mut rng, 300);
+	for _ in 0..200 {
+		let start = rng.gen_range(-110..110);
+		let end = rng.gen_range(start..120);
+		let bounds = match rng.gen_range(0..5) {
+			0 => (Bound::Included(start), Bound::Included(end)),
+			1 => (Bound::Included(start), Bound::Excluded(end + 1)),
+			2 => (Bound::Excluded(start), Bound::Included(end)),
+			3 => (Bound::Unbounded, Bound::Excluded(end)),
+			_ => (Bound::Excluded(start), Bound::Unbounded)
+		};
+		let in_bounds = expected.iter().copied().filter(|it| bounds.contains(it)).collect::<Vec<_>>();
+		assert_eq!(list.range(bounds).collect::<Vec<_>>(), in_bounds);
+		assert_eq!(list.range(bounds).rev().collect::<Vec<_>>(), in_bounds.iter().rev().copied().collect::<Vec<_>>());
+
+		let mut range = list.range(bounds);
+		let (mut front, mut back) = (0, in_bounds.len());
+		while front < back {
+			if rng.gen() {
+				assert_eq!(range.next(), Some(in_bounds[front]));
+				front += 1;
+			} else {
+				back -= 1;
+				assert_eq!(range.next_back(), Some(in_bounds[back]));
+			}
+		}
+		assert_eq!(range.next(), None);
+		assert_eq!(range.next_back(), None);
+	}
+	assert_eq!(HollowSpacedList::<i64>::new().range(..).next(), None);
+}
+
+#[test]
+#[should_panic]
+fn range_with_start_after_end() {
+	let mut list = HollowSpacedList::<i64>::new();
+	list.insert_node(2);
+	list.range((Bound::Included(3), Bound::Excluded(2)));
+}
+
+#[test]
+fn cursor_mut() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let mut list = FilledSpacedList::<i64, usize>::new();
+	// the values are unique, so the cursor must rest on exactly the expected node
+	let mut expected = Vec::<(i64, usize)>::new();
+	// the index of the node the cursor rests on, None for node zero
+	let mut index: Option<usize> = None;
+	let mut cursor = list.cursor_mut();
+	for value in 0..5000 {
+		let position = index.map_or(0, |index| expected[index].0);
+		match rng.gen_range(0..6) {
+			0 if cursor.has_next() => {
+				cursor.advance();
+				index = Some(index.map_or(0, |index| index + 1));
+			}
+			1 if cursor.has_previous() || index.is_some() => {
+				cursor.retreat();
+				index = index.unwrap().checked_sub(1);
+			}
+			2 => {
+				let offset = rng.gen_range(-20..20);
+				cursor.insert_node_with_value(offset, value);
+				let new_index = expected.partition_point(|&(it, _)| it <= position + offset);
+				expected.insert(new_index, (position + offset, value));
+				if let Some(index) = &mut index {
+					if new_index <= *index {
+						*index += 1;
+					}
+				}
+			}
+			3 if index.is_some() => {
+				cursor.remove_node();
+				expected.remove(index.unwrap());
+				index = index.unwrap().checked_sub(1);
+			}
+			4 => {
+				let amount = rng.gen_range(0..5);
+				cursor.inflate_after(amount);
+				for (position, _) in &mut expected[index.map_or(0, |index| index + 1)..] {
+					*position += amount;
+				}
+			}
+			5 => {
+				let next = index.map_or(0, |index| index + 1);
+				if next < expected.len() && expected[next].0 >= position {
+					let amount = rng.gen_range(0..=expected[next].0 - position);
+					cursor.deflate_after(amount);
+					for (position, _) in &mut expected[next..] {
+						*position -= amount;
+					}
+				}
+			}
+			_ => {}
+		}
+		assert_eq!(cursor.is_at_node_zero(), index.is_none());
+		assert_eq!(cursor.has_next(), index.map_or(0, |index| index + 1) < expected.len());
+		assert_eq!(cursor.position(), index.map_or(0, |index| expected[index].0));
+		if let Some(index) = index {
+			let traversal = cursor.traversal();
+			assert_eq!(*traversal.list.value_at(traversal.node_index), expected[index].1);
+		}
+	}
+	assert_eq!(entries(&list), expected);
+}
+
+#[test]
+fn search_at_length() {
+	let mut list = HollowSpacedList::<i64>::new();
+	for size in 1..=64 {
+		list.insert_node(size);
+		assert_eq!(list.node_at(size).map(|it| it.position), Some(size));
+		assert_eq!(list.node_at_or_after(size).map(|it| it.position), Some(size));
+		assert_eq!(list.node_at_or_before(size).map(|it| it.position), Some(size));
+		assert_eq!(list.node_before(size).map(|it| it.position), (size > 1).then_some(size - 1));
+		assert!(list.node_after(size).is_none());
+		assert!(list.node_after(size + 1).is_none());
+	}
+}
+
+#[test]
+fn try_methods() {
+	let mut list = HollowSpacedList::<i8>::new();
+	assert_eq!(list.traversal().try_advance().err(), Some(SpacedListError::NoNextNode));
+	assert_eq!(list.traversal().try_retreat().err(), Some(SpacedListError::NoPreviousNode));
+	assert_eq!(list.try_insert_node(100), Ok(()));
+	assert_eq!(list.try_insert_node(-20), Ok(()));
+	assert_eq!(list.try_insert_node(-100), Err(SpacedListError::PositionOverflow));
+	assert_eq!(list.try_inflate_after(0, -1), Err(SpacedListError::NegativeAmount));
+	assert_eq!(list.try_inflate_after(0, 5), Ok(()));
+	assert_eq!(list.try_inflate_after(0, 5), Err(SpacedListError::PositionOverflow));
+	assert_eq!(list.try_deflate_after(-20, 126), Err(SpacedListError::DeflationPastNode));
+	assert_eq!(list.try_deflate_after(-20, 25), Ok(()));
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![-20, 80]);
+
+	let traversal = list.traversal().try_advance().unwrap().try_advance().unwrap();
+	assert_eq!(traversal.position, 80);
+	assert_eq!(traversal.clone().try_advance().err(), Some(SpacedListError::NoNextNode));
+	assert_eq!(traversal.try_retreat().map(|it| it.position), Ok(-20));
+
+	// the distance from -100 to the node at 100 doesn't fit into an i8
+	let mut list = HollowSpacedList::<i8>::new();
+	assert_eq!(list.try_insert_node(100), Ok(()));
+	assert_eq!(list.try_deflate_after(-100, 1), Ok(()));
+	assert_eq!(list.try_deflate_after(50, 127), Err(SpacedListError::DeflationPastNode));
+	list.deflate_after(-100, 2);
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![97]);
+}
+
+#[test]
+fn fallible_allocation() {
+	let mut list = FilledSpacedList::<i64, usize>::new();
+	assert_eq!(list.try_reserve(usize::MAX), Err(SpacedListError::AllocationFailed));
+	assert_eq!(list.try_reserve(1 << 60), Err(SpacedListError::AllocationFailed));
+	assert_eq!(list.capacity(), 0);
+	assert_eq!(list.try_reserve(5), Ok(()));
+	assert_eq!(list.capacity(), 8);
+	for distance in 0..8 {
+		assert_eq!(list.try_append_node(distance), Ok(()));
+	}
+	assert_eq!(list.capacity(), 8);
+	assert_eq!(list.try_append_node(1), Ok(()));
+	assert_eq!(list.capacity(), 16);
+
+	let mut rng = StdRng::seed_from_u64(4);
+	let mut list = HollowSpacedList::<i64>::new();
+	let mut expected = random_positions(&mut rng, 500);
+	for &position in &expected {
+		assert_eq!(list.try_insert_node(position), Ok(()));
+	}
+	expected.sort();
+	assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+	assert_eq!(list.validate(), Ok(()));
+}
+
+#[test]
+fn rank_and_select() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let (mut list, mut expected) = random_list(&mut rng, 200);
+	for _ in 0..1000 {
+		let position = rng.gen_range(-100..100);
+		if rng.gen_bool(0.6) {
+			list.insert_node(position);
+			let index = expected.partition_point(|&it| it <= position);
+			expected.insert(index, position);
+		} else if let Some(index) = expected.iter().position(|&it| it == position) {
+			list.remove_node_at(position);
+			expected.remove(index);
+		}
+		let position = rng.gen_range(-110..110);
+		assert_eq!(list.rank(position), expected.partition_point(|&it| it < position));
+		let index = rng.gen_range(0..expected.len() + 5);
+		assert_eq!(list.select(index), expected.get(index).copied());
+		assert_eq!(list.deep_size(), expected.len());
+	}
+	for (index, &position) in expected.iter().enumerate() {
+		assert_eq!(list.select(index), Some(position));
+		assert_eq!(list.nth_node(index).map(|it| it.node_count_up_to()), Some(index + 1));
+		let last_index = expected.partition_point(|&it| it <= position) - 1;
+		assert_eq!(list.index_of(position), Some(last_index));
+	}
+	assert_eq!(list.index_of(1000), None);
+}
+
+#[test]
+fn validate() {
+	let mut rng = StdRng::seed_from_u64(0);
+	let mut list = HollowSpacedList::<i64>::new();
+	assert_eq!(list.validate(), Ok(()));
+	for _ in 0..300 {
+		list.insert_node(rng.gen_range(-100..100));
+		assert_eq!(list.validate(), Ok(()));
+	}
+	for _ in 0..300 {
+		list.remove_node_at(rng.gen_range(-100..100));
+		list.inflate_after(rng.gen_range(-100..100), rng.gen_range(0..3));
+		assert_eq!(list.validate(), Ok(()));
+	}
+
+	let mut list = HollowSpacedList::<i64>::new();
+	for position in [0, 10, 20, 5, 7, 6] {
+		list.insert_node(position);
+	}
+	assert_eq!(list.validate(), Ok(()));
+
+	list.descendant_mut(&[1, 1]).skeleton_mut().inflate_at(0, 5);
+	assert_eq!(list.validate(), Err(InvariantViolation {
+		sublist_path: vec![1],
+		kind: ViolationKind::SublistOutOfBounds { node_index: 1 },
+	}));
+	list.descendant_mut(&[1, 1]).skeleton_mut().deflate_at(0, 5);
+
+	list.descendant_mut(&[1]).skeleton_mut().inflate_at(0, 10);
+	assert_eq!(list.validate(), Err(InvariantViolation {
+		sublist_path: vec![],
+		kind: ViolationKind::SublistOutOfBounds { node_index: 1 },
+	}));
+	list.descendant_mut(&[1]).skeleton_mut().deflate_at(0, 10);
+
+	// moving the nodes of a sublist without telling its parent changes the gaps around them
+	list.descendant_mut(&[1]).skeleton_mut().deflate_at(0, 1);
+	assert_eq!(list.validate(), Err(InvariantViolation {
+		sublist_path: vec![],
+		kind: ViolationKind::InvalidLargestGap { link_index: 1 },
+	}));
+	list.descendant_mut(&[1]).skeleton_mut().inflate_at(0, 1);
+
+	*list.skeleton_mut().get_link_length_at_mut(2) += 100;
+	assert_eq!(list.validate(), Err(InvariantViolation {
+		sublist_path: vec![],
+		kind: ViolationKind::DistanceAfterLastNode { link_index: 3 },
+	}));
+	*list.skeleton_mut().get_link_length_at_mut(2) -= 100;
+
+	*list.slot_count_mut() += 2;
+	assert_eq!(list.validate(), Err(InvariantViolation {
+		sublist_path: vec![],
+		kind: ViolationKind::InvalidSize,
+	}));
+	*list.slot_count_mut() -= 2;
+	assert_eq!(list.validate(), Ok(()));
+}
+
+#[cfg(feature = "paranoid")]
+#[test]
+// the link out of node 1 and node 1 are highlighted, but node 1 shows its sublist instead
+#[should_panic(expected = "SublistOutOfBounds { node_index: 1 } in the list at sublist path []
+╭───────────────╮
+┝━━━━━━━┑       │
+├───╮   ├───╮   │
+╵   ╰000╵   ╵   ╵
+ ┍╸000: 
+ │ ╭───╮
+ │ ╵   ╵
+")]
+fn paranoid_mutation_of_broken_list() {
+	let mut list = HollowSpacedList::<i64>::new();
+	for position in [0, 10, 20, 5] {
+		list.insert_node(position);
+	}
+	list.descendant_mut(&[1]).skeleton_mut().inflate_at(0, 10);
+	list.insert_node(30);
+}
+
+#[test]
+fn filled_insert_get_and_remove() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let (mut list, mut expected) = random_filled_list(&mut rng, 300);
+	assert_eq!(entries(&list), expected);
+
+	for position in -100..100 {
+		let value = expected.iter().rev().find(|&&(it, _)| it == position).map(|(_, value)| value);
+		assert_eq!(list.get(position), value);
+	}
+
+	list.inflate_after(0, 7);
+	for (position, _) in &mut expected {
+		if *position > 0 {
+			*position += 7;
+		}
+	}
+	*list.get_mut(7 + 10).unwrap() += 1000;
+	let index = expected.iter().rposition(|&(it, _)| it == 7 + 10).unwrap();
+	expected[index].1 += 1000;
+	assert_eq!(entries(&list), expected);
+
+	for (index, &(_, value)) in expected.iter().enumerate() {
+		assert_eq!(list[index], value);
+	}
+	list[3] += 1000;
+	expected[3].1 += 1000;
+	assert_eq!(list.get_by_index(3), Some(&expected[3].1));
+	assert_eq!(list.get_by_index(expected.len()), None);
+
+	let in_range = expected.iter().copied().filter(|&(it, _)| (-10..20).contains(&it)).collect::<Vec<_>>();
+	assert_eq!(list.range_with_values(-10..20).map(|(position, &value)| (position, value)).collect::<Vec<_>>(),
+	           in_range);
+	assert_eq!(list.range_with_values(-10..20).rev().count(), in_range.len());
+
+	while !expected.is_empty() {
+		let position = rng.gen_range(-100..107);
+		let index = expected.iter().rposition(|&(it, _)| it == position);
+		assert_eq!(list.remove(position), index.map(|index| expected.remove(index).1));
+		assert_eq!(entries(&list), expected);
+	}
+	assert!(list.is_empty());
+}
+
+/// The values of a sequence of nodes in order, which checks that they are combined in the right
+/// order.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct InOrder(Vec<u32>);
+
+impl Aggregate<u32> for InOrder {
+	fn empty() -> Self {
+		Self(vec![])
+	}
+
+	fn of(value: &u32) -> Self {
+		Self(vec![*value])
+	}
+
+	fn combine(&self, other: &Self) -> Self {
+		Self([self.0.as_slice(), other.0.as_slice()].concat())
+	}
+}
+
+#[test]
+fn aggregated_ranges() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let mut list = AggregatedSpacedList::<i64, u32, InOrder>::new();
+	let mut expected = Vec::<(i64, u32)>::new();
+	let aggregate_of = |expected: &[(i64, u32)], bounds: (Bound<i64>, Bound<i64>)| {
+		InOrder(expected.iter().filter(|(it, _)| bounds.contains(it)).map(|&(_, value)| value).collect())
+	};
+	for step in 0..1500 {
+		match rng.gen_range(0..6) {
+			0..=2 => {
+				let position = rng.gen_range(-60..60);
+				let value = rng.gen();
+				list.insert(position, value);
+				let index = expected.partition_point(|&(it, _)| it <= position);
+				expected.insert(index, (position, value));
+			}
+			3 => {
+				let position = rng.gen_range(-60..60);
+				let index = expected.iter().rposition(|&(it, _)| it == position);
+				assert_eq!(list.remove(position), index.map(|index| expected.remove(index).1));
+			}
+			4 => {
+				let position = rng.gen_range(-60..60);
+				let value = rng.gen();
+				let index = expected.iter().rposition(|&(it, _)| it == position);
+				let previous = index.map(|index| std::mem::replace(&mut expected[index].1, value));
+				assert_eq!(list.set(position, value), previous);
+			}
+			_ => {
+				let position = rng.gen_range(-60..60);
+				let amount = rng.gen_range(0..3);
+				list.inflate_after(position, amount);
+				for (it, _) in &mut expected {
+					if *it > position {
+						*it += amount;
+					}
+				}
+			}
+		}
+		assert_eq!(list.aggregate(), aggregate_of(&expected, (Bound::Unbounded, Bound::Unbounded)));
+		for _ in 0..5 {
+			let bound = |rng: &mut StdRng| match rng.gen_range(0..3) {
+				0 => Bound::Included(rng.gen_range(-70..70)),
+				1 => Bound::Excluded(rng.gen_range(-70..70)),
+				_ => Bound::Unbounded
+			};
+			let bounds = (bound(&mut rng), bound(&mut rng));
+			assert_eq!(list.aggregate_range(bounds), aggregate_of(&expected, bounds), "{bounds:?} at step {step}");
+		}
+	}
+}
+
+#[test]
+fn split_off() {
+	let mut rng = StdRng::seed_from_u64(4);
+	for _ in 0..50 {
+		let count = rng.gen_range(0..200);
+		let (mut list, mut expected) = random_list(&mut rng, count);
+		let position = rng.gen_range(-110..110);
+		let tail = list.split_off(position);
+		let index = expected.partition_point(|&it| it < position);
+		let expected_tail = expected.split_off(index).into_iter().map(|it| it - position).collect::<Vec<_>>();
+		assert_eq!(list.validate(), Ok(()));
+		assert_eq!(tail.validate(), Ok(()));
+		assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+		assert_eq!(tail.iter().collect::<Vec<_>>(), expected_tail);
+		assert_eq!(tail.deep_size(), expected_tail.len());
+	}
+
+	let mut list = FilledSpacedList::<i64, usize>::new();
+	for (value, position) in [5, 0, 10, 5, 3, 7, 12].into_iter().enumerate() {
+		list.insert(position, value);
+	}
+	let tail = list.split_off(5);
+	assert_eq!(entries(&list), vec![(0, 1), (3, 4)]);
+	assert_eq!(entries(&tail), vec![(0, 0), (0, 3), (2, 5), (5, 2), (7, 6)]);
+}
+
+#[test]
+fn append_list() {
+	let mut rng = StdRng::seed_from_u64(5);
+	let mut list = HollowSpacedList::<i64>::new();
+	let mut expected = Vec::<i64>::new();
+	for _ in 0..30 {
+		let count = rng.gen_range(0..40);
+		let (other, other_positions) = random_list(&mut rng, count);
+		let first = other_positions.first().copied().unwrap_or(0);
+		let gap = rng.gen_range(0..10) - first;
+		let last = expected.last().copied().unwrap_or(0);
+		list.append_list(gap, other);
+		expected.extend(other_positions.into_iter().map(|it| last + gap + it));
+		assert_eq!(list.validate(), Ok(()));
+		assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+		assert_eq!(list.deep_size(), expected.len());
+	}
+
+	let position = expected[expected.len() / 2];
+	let tail = list.split_off(position);
+	list.append_list(position - list.length(), tail);
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn append_list_to_empty_list() {
+	let mut list = HollowSpacedList::<i64>::new();
+	for position in [-50, 0, -20, 30, -50] {
+		list.insert_node(position);
+	}
+	let tail = list.split_off(-60);
+	assert!(list.is_empty());
+	list.append_list(-60, tail);
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![-50, -50, -20, 0, 30]);
+}
+
+#[test]
+#[should_panic(expected = "Cannot append a list whose first node would be before the last node")]
+fn append_list_before_last_node() {
+	let mut list = HollowSpacedList::<i64>::new();
+	list.insert_node(5);
+	let mut other = HollowSpacedList::<i64>::new();
+	other.insert_node(-3);
+	list.append_list(2, other);
+}
+
+#[test]
+fn from_sorted_positions() {
+	let mut rng = StdRng::seed_from_u64(6);
+	for size in [0usize, 1, 2, 3, 4, 5, 100, 128, 129] {
+		let mut positions = (0..size).map(|_| rng.gen_range(-100..100)).collect::<Vec<i64>>();
+		positions.sort();
+		let mut list = HollowSpacedList::from_sorted_positions(positions.iter().copied());
+		assert_eq!(list.validate(), Ok(()));
+		assert_eq!(list.capacity(), if size == 0 { 0 } else { size.next_power_of_two() });
+		assert_eq!(list.iter().collect::<Vec<_>>(), positions);
+		assert_eq!(list.deep_size(), size);
+		list.insert_node(0);
+		list.remove_node_at(0);
+		assert_eq!(list.validate(), Ok(()));
+		assert_eq!(list.iter().collect::<Vec<_>>(), positions);
+	}
+}
+
+#[test]
+#[should_panic(expected = "Positions must be sorted")]
+fn from_unsorted_positions() {
+	HollowSpacedList::from_sorted_positions([1, 3, 2]);
+}
+
+#[test]
+fn from_unsorted_and_extend() {
+	let positions = [5, -3, 8, 5, 0, 8, 8, 2];
+	let list = HollowSpacedList::from_unsorted(positions, DuplicatePolicy::Keep);
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![-3, 0, 2, 5, 5, 8, 8, 8]);
+	assert_eq!(list.validate(), Ok(()));
+	let list = HollowSpacedList::from_unsorted(positions, DuplicatePolicy::Remove);
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![-3, 0, 2, 5, 8]);
+	assert_eq!(list.validate(), Ok(()));
+	let list = positions.into_iter().collect::<HollowSpacedList<_>>();
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![-3, 0, 2, 5, 5, 8, 8, 8]);
+	assert_eq!(list.validate(), Ok(()));
+
+	let mut rng = StdRng::seed_from_u64(7);
+	let mut list = HollowSpacedList::<i64>::new();
+	let mut expected = Vec::<i64>::new();
+	for _ in 0..20 {
+		// every other batch only extends the list past its end
+		let start = if rng.gen_bool(0.5) { -100 } else { list.length() };
+		let positions = (0..rng.gen_range(0..50)).map(|_| rng.gen_range(start..start + 200)).collect::<Vec<_>>();
+		list.extend(positions.iter().copied());
+		expected.extend(positions);
+		expected.sort();
+		assert_eq!(list.validate(), Ok(()));
+		assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+		assert_eq!(list.skeleton().sublists().count(), 0);
+	}
+}
+
+fn nesting_depth(list: &HollowSpacedList<i64>) -> usize {
+	1 + list.skeleton().sublists().map(nesting_depth).max().unwrap_or(0)
+}
+
+#[test]
+fn rebalance() {
+	// inserting in front of all nodes but the last makes every node take the place of the previous
+	// one, moving it into its sublist
+	let mut list = HollowSpacedList::<i64>::new();
+	list.insert_node(0);
+	list.insert_node(10000);
+	for position in (1..2000).rev() {
+		list.insert_node(position);
+	}
+	assert_eq!(list.validate(), Ok(()));
+	assert!(nesting_depth(&list) <= 12, "nesting depth {}", nesting_depth(&list));
+	let expected = list.iter().collect::<Vec<_>>();
+	assert_eq!(expected.len(), 2001);
+
+	list.rebalance();
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(nesting_depth(&list), 1);
+	assert_eq!(list.size(), 2001);
+	assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+
+	let mut rng = StdRng::seed_from_u64(8);
+	let (mut list, expected) = random_filled_list(&mut rng, 500);
+	list.rebalance();
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(list.size(), expected.len());
+	assert_eq!(entries(&list), expected);
+}
+
+#[test]
+fn capacity() {
+	let mut list = HollowSpacedList::<i64>::with_capacity(100);
+	assert_eq!(list.capacity(), 128);
+	for position in 0..128 {
+		list.insert_node(position);
+	}
+	assert_eq!(list.capacity(), 128);
+	list.reserve(1);
+	assert_eq!(list.capacity(), 256);
+	list.shrink_to_fit();
+	assert_eq!(list.capacity(), 128);
+	assert_eq!(list.validate(), Ok(()));
+
+	// removing nodes shrinks the list once at most a quarter of its capacity is used
+	for position in (40..128).rev() {
+		list.remove_node_at(position);
+		assert!(list.capacity() <= 4 * list.size().max(1), "capacity {} for size {}", list.capacity(), list.size());
+		assert_eq!(list.validate(), Ok(()));
+	}
+	assert_eq!(list.capacity(), 128);
+	list.shrink_to_fit();
+	assert_eq!(list.capacity(), 64);
+	assert_eq!(list.iter().collect::<Vec<_>>(), (0..40).collect::<Vec<_>>());
+	for position in (0..40).rev() {
+		list.remove_node_at(position);
+	}
+	assert_eq!(list.capacity(), 0);
+	list.shrink_to_fit();
+	assert_eq!(list.capacity(), 0);
+	assert_eq!(list.validate(), Ok(()));
+
+	// splitting off all but a few nodes shrinks the list more than once
+	let mut list = HollowSpacedList::<i64>::from_sorted_positions(0..1024);
+	let tail = list.split_off(10);
+	assert_eq!(list.size(), 10);
+	assert_eq!(list.capacity(), 32);
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(tail.size(), 1014);
+	assert_eq!(tail.iter().next(), Some(0));
+
+	let mut rng = StdRng::seed_from_u64(9);
+	let (mut list, mut expected) = random_filled_list(&mut rng, 300);
+	list.shrink_to_fit();
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(entries(&list), expected);
+	while expected.len() > 10 {
+		let position = expected[rng.gen_range(0..expected.len())].0;
+		let index = expected.iter().rposition(|&(it, _)| it == position).unwrap();
+		assert_eq!(list.remove(position), Some(expected.remove(index).1));
+		assert!(list.capacity() <= 4 * list.size().max(1));
+	}
+	assert_eq!(list.validate(), Ok(()));
+	assert_eq!(entries(&list), expected);
+}
+
+#[test]
+fn memory_usage() {
+	assert_eq!(HollowSpacedList::<i64>::new().memory_usage(), MemoryUsage::default());
+
+	let list = HollowSpacedList::<i64>::from_sorted_positions([0, 1, 5]);
+	let link_bytes = 3 * size_of::<i64>() + size_of::<usize>() + size_of::<Option<HollowSpacedList<i64>>>()
+		+ size_of::<bool>();
+	let usage = list.memory_usage();
+	assert_eq!(usage, MemoryUsage {
+		skeleton_bytes: 4 * link_bytes,
+		sublist_bytes: 0,
+		wasted_capacity_bytes: link_bytes,
+		depth_histogram: vec![3],
+	});
+	assert_eq!(usage.total_bytes(), 4 * link_bytes);
+
+	let mut rng = StdRng::seed_from_u64(12);
+	let mut list = FilledSpacedList::<i64, u64>::new();
+	for value in 0..300 {
+		list.insert(rng.gen_range(-100..100), value);
+	}
+	let usage = list.memory_usage();
+	assert!(usage.sublist_bytes > 0);
+	assert!(usage.depth_histogram.len() > 1);
+	assert_eq!(usage.depth_histogram.iter().sum::<usize>(), list.deep_size());
+	assert_eq!(usage.depth_histogram[0], list.size());
+
+	list.rebalance();
+	list.shrink_to_fit();
+	let usage = list.memory_usage();
+	assert_eq!(usage.depth_histogram, vec![300]);
+	assert_eq!(usage.sublist_bytes, 0);
+	// the skeleton has room for 512 nodes, and the values for at least 300
+	let link_bytes = 3 * size_of::<i64>() + size_of::<usize>() + size_of::<Option<FilledSpacedList<i64, u64>>>()
+		+ size_of::<bool>();
+	let value_bytes = size_of::<Option<u64>>();
+	assert_eq!(list.capacity(), 512);
+	assert!(usage.skeleton_bytes >= 512 * link_bytes + 300 * value_bytes);
+	assert_eq!(usage.wasted_capacity_bytes, usage.skeleton_bytes - 300 * (link_bytes + value_bytes));
+}
+
+#[test]
+fn gaps() {
+	assert_eq!(HollowSpacedList::<i64>::new().largest_gap(), None);
+	assert_eq!(HollowSpacedList::<i64>::new().first_gap_at_least(0), None);
+	let mut rng = StdRng::seed_from_u64(3);
+	let (mut list, mut expected) = random_list(&mut rng, 100);
+	for _ in 0..1000 {
+		if rng.gen_bool(0.5) {
+			let position = rng.gen_range(-200..200);
+			list.insert_node(position);
+			let index = expected.partition_point(|&it| it <= position);
+			expected.insert(index, position);
+		} else if rng.gen_bool(0.5) {
+			let position = rng.gen_range(-200..200);
+			if list.remove_node_at(position) {
+				let index = expected.iter().rposition(|&it| it == position).unwrap();
+				expected.remove(index);
+			}
+		} else {
+			let position = rng.gen_range(-200..200);
+			let amount = rng.gen_range(0..20);
+			list.inflate_after(position, amount);
+			for it in &mut expected {
+				if *it > position {
+					*it += amount;
+				}
+			}
+		}
+		let gaps = expected.windows(2).map(|pair| (pair[0], pair[1] - pair[0])).collect::<Vec<_>>();
+		assert_eq!(list.largest_gap(), gaps.iter().map(|&(_, gap)| gap).max());
+		for size in [-1, 0, 1, 5, 10, 20, 40, 80] {
+			let first = gaps.iter().find(|&&(_, gap)| gap >= size).map(|&(position, _)| position);
+			assert_eq!(list.first_gap_at_least(size), first);
+		}
+	}
+}
+
+#[test]
+fn ranges() {
+	let mut list = RangeSpacedList::<i64>::new();
+	for range in [10..20, 30..35, 20..25, 0..5, 25..30, 6..8] {
+		list.insert_range(range);
+	}
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![0..5, 6..8, 10..20, 20..25, 25..30, 30..35]);
+	assert_eq!(list.range_containing(20), Some(20..25));
+	assert_eq!(list.range_containing(19), Some(10..20));
+	assert_eq!(list.range_containing(5), None);
+	assert_eq!(list.range_containing(35), None);
+
+	// inside a range, at its start and before it
+	list.inflate_after(12, 3);
+	list.inflate_after(25, 1);
+	list.inflate_after(-1, 1);
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![1..6, 7..9, 11..24, 24..30, 30..35, 35..40]);
+
+	list.deflate_after(24, 4);
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![1..6, 7..9, 11..24, 24..26, 26..31, 31..36]);
+
+	assert_eq!(list.remove_range_containing(24), Some(24..26));
+	assert_eq!(list.remove_range_containing(24), None);
+	assert_eq!(list.remove_range_containing(11), Some(11..24));
+	list.insert_range(9..26);
+	assert_eq!(list.iter().collect::<Vec<_>>(), vec![1..6, 7..9, 9..26, 26..31, 31..36]);
+	assert_eq!(list.size(), 5);
+	assert_eq!(list.length(), 36);
+}
+
+#[test]
+#[should_panic]
+fn overlapping_ranges() {
+	let mut list = RangeSpacedList::<i64>::new();
+	list.insert_range(10..20);
+	list.insert_range(5..11);
+}
+
+#[test]
+fn filled_ranges() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let mut list = FilledRangeSpacedList::<i64, usize>::new();
+	let mut expected = Vec::<(i64, i64, usize)>::new();
+	for value in 0..200 {
+		let start = rng.gen_range(0..1000);
+		let end = start + rng.gen_range(1..10);
+		if expected.iter().all(|&(other_start, other_end, _)| end <= other_start || other_end <= start) {
+			list.insert_range(start..end, value);
+			let index = expected.partition_point(|&(it, _, _)| it < start);
+			expected.insert(index, (start, end, value));
+		}
+	}
+	let ranges = list.iter().map(|(range, &value)| (range.start, range.end, value)).collect::<Vec<_>>();
+	assert_eq!(ranges, expected);
+
+	for &(start, end, value) in &expected {
+		assert_eq!(list.range_containing(start), Some((start..end, &value)));
+		assert_eq!(list.range_containing(end - 1), Some((start..end, &value)));
+	}
+	while let Some((start, end, value)) = expected.pop() {
+		assert_eq!(list.remove_range_containing(start), Some((start..end, value)));
+	}
+	assert!(list.is_empty());
+}
+
+#[test]
+fn nested_ranges() {
+	let sides = [StickySide::Left, StickySide::Right];
+	for (seed, (start_side, end_side)) in sides.iter().flat_map(|&a| sides.iter().map(move |&b| (a, b))).enumerate() {
+		let mut rng = StdRng::seed_from_u64(seed as u64);
+		let mut list = NestedRangeSpacedList::<i64, usize>::with_sticky_sides(start_side, end_side);
+		let mut expected = Vec::<(i64, i64, usize)>::new();
+		for value in 0..300 {
+			match rng.gen_range(0..4) {
+				0 | 1 => {
+					let start = rng.gen_range(0..100);
+					let end = start + rng.gen_range(1..20);
+					list.insert_range(start..end, value);
+					expected.push((start, end, value));
+				}
+				2 => {
+					let position = rng.gen_range(-1..120);
+					let amount = rng.gen_range(0..4);
+					list.inflate_at(position, amount);
+					for (start, end, _) in &mut expected {
+						if *start > position || *start == position && start_side == StickySide::Right {
+							*start += amount;
+						}
+						if *end > position || *end == position && end_side == StickySide::Right {
+							*end += amount;
+						}
+					}
+				}
+				_ => {
+					if let Some(&(start, end, _)) = expected.get(rng.gen_range(0..expected.len().max(1))) {
+						let removed = list.remove_range(start..end).unwrap();
+						let index = expected.iter().position(|&it| it == (start, end, removed)).unwrap();
+						expected.remove(index);
+					}
+				}
+			}
+			assert_eq!(list.size(), expected.len());
+			let position = rng.gen_range(0..140);
+			let mut containing = expected.iter()
+			                             .filter(|&&(start, end, _)| start <= position && position < end)
+			                             .map(|&(start, end, _)| (start, end))
+			                             .collect::<Vec<_>>();
+			containing.sort();
+			let actual = list.ranges_containing(position).into_iter().map(|(range, _)| (range.start, range.end));
+			assert_eq!(actual.collect::<Vec<_>>(), containing);
+
+			let end = position + rng.gen_range(0..10);
+			let mut overlapping = expected.iter()
+			                              .filter(|&&(start, other_end, _)| start < end && other_end > position)
+			                              .copied()
+			                              .collect::<Vec<_>>();
+			let mut actual = list.ranges_overlapping(position..end).into_iter()
+			                     .map(|(range, &value)| (range.start, range.end, value))
+			                     .collect::<Vec<_>>();
+			// ranges with equal bounds can be in any order
+			overlapping.sort();
+			actual.sort();
+			assert_eq!(actual, overlapping);
+		}
+	}
+}
+
+#[test]
+#[cfg_attr(feature = "paranoid", ignore)]
+fn nested_range_queries_under_a_long_range() {
+	// walking the bounds of the short ranges would take about 200000 steps per query
+	let mut list = NestedRangeSpacedList::<i64, i64>::new();
+	list.insert_range(0..200_000, -1);
+	for start in 0..100_000 {
+		list.insert_range(2 * start..2 * start + 1, start);
+	}
+	let start = Instant::now();
+	for position in (20..200_000).step_by(20) {
+		let containing = list.ranges_containing(position);
+		assert_eq!(containing.len(), 2);
+		assert_eq!(containing[0], (0..200_000, &-1));
+		assert_eq!(containing[1], (position..position + 1, &(position / 2)));
+		assert_eq!(list.ranges_overlapping(position + 1..position + 4).len(), 2);
+	}
+	let elapsed = start.elapsed();
+	assert!(elapsed < Duration::from_secs(2), "20000 queries took {elapsed:?}");
+	assert_eq!(list.remove_range(0..200_000), Some(-1));
+	assert_eq!(list.remove_range(10..11), Some(5));
+	assert_eq!(list.ranges_overlapping(9..13), vec![(12..13, &6)]);
+}